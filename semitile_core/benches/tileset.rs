@@ -0,0 +1,38 @@
+// Copyright (C) 2025 Connor Nolan connor@cnolandev.com
+//
+// This file is part of the Semikit project.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use semitile_core::{Tile, Tileset};
+
+fn add_unique_dedup(c: &mut Criterion) {
+    let distinct: Vec<Tile> = (0..500).map(Tile::pseudo_random).collect();
+    c.bench_function("tileset_add_unique_10k", |b| {
+        b.iter(|| {
+            let mut tileset = Tileset::new();
+            for i in 0..10_000 {
+                let tile = distinct[(i * 7) % distinct.len()].clone();
+                black_box(tileset.add_unique(tile).unwrap());
+            }
+            tileset
+        })
+    });
+}
+
+criterion_group!(benches, add_unique_dedup);
+criterion_main!(benches);
@@ -0,0 +1,150 @@
+// Copyright (C) 2025 Connor Nolan connor@cnolandev.com
+//
+// This file is part of the Semikit project.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+
+/// Error type shared by fallible Semitile operations
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SemitileError {
+    /// Input data had the wrong length
+    InvalidLength { expected: usize, actual: usize },
+    /// An index fell outside the allowed inclusive range
+    OutOfBounds {
+        index: usize,
+        min: usize,
+        max: usize,
+    },
+    /// A container was already holding its maximum number of items
+    CapacityExceeded { capacity: usize },
+    /// Input data could not be parsed
+    Parse(String),
+    /// Output data could not be encoded
+    Encode(String),
+}
+
+impl fmt::Display for SemitileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength { expected, actual } => {
+                write!(
+                    f,
+                    "invalid length: expected {} bytes, got {}",
+                    expected, actual
+                )
+            }
+            Self::OutOfBounds { index, min, max } => {
+                write!(f, "index {} out of bounds ({}-{})", index, min, max)
+            }
+            Self::CapacityExceeded { capacity } => {
+                write!(f, "capacity of {} exceeded", capacity)
+            }
+            Self::Parse(message) => write!(f, "parse error: {}", message),
+            Self::Encode(message) => write!(f, "encode error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SemitileError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tile, Tilemap, TilemapEntry, Tileset};
+
+    #[test]
+    fn test_error_display() {
+        let err = SemitileError::InvalidLength {
+            expected: 32,
+            actual: 5,
+        };
+        assert_eq!(err.to_string(), "invalid length: expected 32 bytes, got 5");
+
+        let err = SemitileError::OutOfBounds {
+            index: 1024,
+            min: 0,
+            max: 1023,
+        };
+        assert_eq!(err.to_string(), "index 1024 out of bounds (0-1023)");
+
+        let err = SemitileError::CapacityExceeded { capacity: 1024 };
+        assert_eq!(err.to_string(), "capacity of 1024 exceeded");
+
+        let err = SemitileError::Parse("bad header".to_string());
+        assert_eq!(err.to_string(), "parse error: bad header");
+
+        let err = SemitileError::Encode("write failed".to_string());
+        assert_eq!(err.to_string(), "encode error: write failed");
+    }
+
+    #[test]
+    fn test_error_out_of_bounds_from_export_with_base() {
+        let mut tilemap = Tilemap::new(1, 1);
+        tilemap.set_entry(0, 0, TilemapEntry::new(5, 0, false, false, false));
+        assert_eq!(
+            tilemap.export_binary_with_base(10),
+            Err(SemitileError::OutOfBounds {
+                index: 5,
+                min: 10,
+                max: 1023
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_capacity_exceeded_from_tileset() {
+        let mut tileset = Tileset::new();
+        while !tileset.is_full() {
+            tileset.add(Tile::new()).unwrap();
+        }
+        assert_eq!(
+            tileset.add(Tile::new()),
+            Err(SemitileError::CapacityExceeded { capacity: 1024 })
+        );
+    }
+
+    #[test]
+    fn test_error_invalid_length_from_import_planar_at() {
+        let mut tileset = Tileset::new();
+        assert_eq!(
+            tileset.import_planar_at(&[0; 40], 0),
+            Err(SemitileError::InvalidLength {
+                expected: 64,
+                actual: 40
+            })
+        );
+        assert!(tileset.is_empty());
+    }
+
+    #[test]
+    fn test_error_parse_from_tilemap_load() {
+        assert_eq!(
+            Tilemap::load(&[0; 16]),
+            Err(SemitileError::Parse(
+                "missing tilemap header (use import_binary for raw data)".to_string()
+            ))
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_error_parse_from_png_palette() {
+        assert!(matches!(
+            crate::Palette::from_png_palette(b"not a png"),
+            Err(SemitileError::Parse(_))
+        ));
+    }
+}
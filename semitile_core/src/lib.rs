@@ -15,10 +15,20 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+pub mod error;
 pub mod palette;
+mod rng;
 pub mod tile;
 pub mod tilemap;
+pub mod tileset;
 
-pub use palette::{Color, Palette};
-pub use tile::Tile;
-pub use tilemap::{Tilemap, TilemapEntry};
+pub use error::SemitileError;
+pub use palette::{Color, ColorBlindness, Palette, preview_quantized_rgba};
+pub use tile::{
+    RenderOptions, Rotation, Tile, iter_tiles_from_planar, pack_planar_row, unpack_planar_row,
+};
+pub use tilemap::{
+    ChangeKind, ChunkMut, ChunkedTilemap, FlipStats, SaveFormat, SuspiciousReason, Tilemap,
+    TilemapEditLog, TilemapEntry,
+};
+pub use tileset::Tileset;
@@ -15,8 +15,39 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::HashSet;
+use std::ops::{Add, Mul, Sub};
+
+use crate::error::SemitileError;
+
+/// 3×5 digit glyphs for contact sheet row labels, one 3-bit mask per line
+#[cfg(feature = "png")]
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Contact sheet label strip colors (background, digits)
+#[cfg(feature = "png")]
+const LABEL_COLORS: [[u8; 3]; 2] = [[32, 32, 32], [255, 255, 255]];
+
 /// Represents a color in RGB555 format (5 bits per channel)
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///
+/// With the `serde` feature, serializes as `[r, g, b]` (each 0-31)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[u8; 3]", into = "[u8; 3]")
+)]
 pub struct Color {
     r: u8, // 0-31 (5 bits)
     g: u8, // 0-31 (5 bits)
@@ -52,6 +83,28 @@ impl Color {
         }
     }
 
+    /// Converts the color to RGB565 format (16-bit) for embedded displays
+    ///
+    /// Format:
+    /// - Bits 11-15: Red (5 bits)
+    /// - Bits 5-10: Green (6 bits, expanded as `(g << 1) | (g >> 4)`)
+    /// - Bits 0-4: Blue (5 bits)
+    pub fn to_rgb565(&self) -> u16 {
+        let g6 = ((self.g as u16) << 1) | ((self.g as u16) >> 4);
+        ((self.r as u16) << 11) | (g6 << 5) | (self.b as u16)
+    }
+
+    /// Creates a color from RGB565 format (16-bit)
+    ///
+    /// The green channel's lowest bit is dropped to fit 5 bits
+    pub fn from_rgb565(value: u16) -> Self {
+        Self {
+            r: ((value >> 11) & 0x1F) as u8,
+            g: ((value >> 6) & 0x1F) as u8,
+            b: (value & 0x1F) as u8,
+        }
+    }
+
     /// Converts the color to RGB888 format for display in browser
     ///
     /// Scales 5-bit values (0-31) to 8-bit values (0-255) using proper expansion:
@@ -77,6 +130,193 @@ impl Color {
         }
     }
 
+    /// Creates a color from RGB888 format, rounding to the nearest 5-bit value
+    ///
+    /// Unlike `from_rgb888()`, which truncates and so darkens images slightly,
+    /// each channel is scaled by 31/255 and rounded. This is the inverse of
+    /// `to_rgb888()`, so expanded colors convert back to themselves exactly.
+    ///
+    /// The simpler `(c + 4) >> 3` is deliberately not used: it rounds to the
+    /// nearest multiple of 8, but `to_rgb888()` replicates the top bits into
+    /// the low ones, so level 16 expands to 132 and `(132 + 4) >> 3` would
+    /// return 17
+    pub fn from_rgb888_rounded(r: u8, g: u8, b: u8) -> Self {
+        let round = |c: u8| ((c as u32 * 31 + 127) / 255) as u8;
+        Self::new(round(r), round(g), round(b))
+    }
+
+    /// Parses a hex color string
+    ///
+    /// Accepts `#RRGGBB` or the shorthand `#RGB`, with or without the leading
+    /// `#`, in either case. The RGB888 value is reduced to 5 bits like
+    /// `from_rgb888()`, so parsing is lossy. Returns None on malformed input
+    pub fn from_hex(s: &str) -> Option<Self> {
+        let digits = s.strip_prefix('#').unwrap_or(s);
+        if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel =
+            |i: usize, len: usize| u8::from_str_radix(&digits[i * len..(i + 1) * len], 16);
+        let (r, g, b) = match digits.len() {
+            3 => (
+                channel(0, 1).ok()? * 0x11,
+                channel(1, 1).ok()? * 0x11,
+                channel(2, 1).ok()? * 0x11,
+            ),
+            6 => (
+                channel(0, 2).ok()?,
+                channel(1, 2).ok()?,
+                channel(2, 2).ok()?,
+            ),
+            _ => return None,
+        };
+        Some(Self::from_rgb888(r, g, b))
+    }
+
+    /// Formats the color as `#RRGGBB` using its `to_rgb888()` expansion
+    pub fn to_hex(&self) -> String {
+        let (r, g, b) = self.to_rgb888();
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    }
+
+    /// Creates a color from normalized float components (0.0-1.0)
+    ///
+    /// Values are clamped to 0.0-1.0 and scaled to the 5-bit range with rounding
+    pub fn from_rgb_f32(r: f32, g: f32, b: f32) -> Self {
+        let scale = |c: f32| (c.clamp(0.0, 1.0) * 31.0).round() as u8;
+        Self::new(scale(r), scale(g), scale(b))
+    }
+
+    /// Converts the color to normalized float components (0.0-1.0)
+    ///
+    /// Returns: (r, g, b) tuple where 31 maps to 1.0
+    pub fn to_rgb_f32(&self) -> (f32, f32, f32) {
+        (
+            self.r as f32 / 31.0,
+            self.g as f32 / 31.0,
+            self.b as f32 / 31.0,
+        )
+    }
+
+    /// Converts the color to YUV (BT.601) from normalized RGB
+    ///
+    /// Returns: (y, u, v) with Y in 0.0-1.0, U in about ±0.436 and V in about ±0.615
+    pub fn to_yuv(&self) -> (f32, f32, f32) {
+        let (r, g, b) = self.to_rgb_f32();
+        let y = self.luminance();
+        let u = -0.14713 * r - 0.28886 * g + 0.436 * b;
+        let v = 0.615 * r - 0.51499 * g - 0.10001 * b;
+        (y, u, v)
+    }
+
+    /// Creates a color from YUV (BT.601) components
+    ///
+    /// The inverse of `to_yuv()`; resulting RGB values are clamped and quantized to 5 bits
+    pub fn from_yuv(y: f32, u: f32, v: f32) -> Self {
+        let r = y + 1.13983 * v;
+        let g = y - 0.39465 * u - 0.58060 * v;
+        let b = y + 2.03211 * u;
+        Self::from_rgb_f32(r, g, b)
+    }
+
+    /// Returns the perceptual brightness (Rec. 601 luma) in 0.0-1.0
+    ///
+    /// Computed as `0.299 * r + 0.587 * g + 0.114 * b` on normalized components
+    pub fn luminance(&self) -> f32 {
+        let (r, g, b) = self.to_rgb_f32();
+        0.299 * r + 0.587 * g + 0.114 * b
+    }
+
+    /// Returns a gray color with all channels set to the rounded `luminance()`
+    pub fn to_grayscale(&self) -> Color {
+        let level = (self.luminance() * 31.0).round() as u8;
+        Self::new(level, level, level)
+    }
+
+    /// Returns the weighted squared distance to another color
+    ///
+    /// Uses integer weights of 2 for red, 4 for green and 3 for blue on the
+    /// 5-bit components, approximating perceived difference. Intended for
+    /// ranking nearest-color candidates, not as an absolute measure.
+    pub fn distance_squared(&self, other: &Color) -> u32 {
+        let dr = self.r.abs_diff(other.r) as u32;
+        let dg = self.g.abs_diff(other.g) as u32;
+        let db = self.b.abs_diff(other.b) as u32;
+        2 * dr * dr + 4 * dg * dg + 3 * db * db
+    }
+
+    /// Adds two colors channel-wise, saturating at 31
+    pub fn saturating_add(&self, other: &Color) -> Color {
+        Self::new(self.r + other.r, self.g + other.g, self.b + other.b)
+    }
+
+    /// Subtracts another color channel-wise, saturating at 0
+    pub fn saturating_sub(&self, other: &Color) -> Color {
+        Self {
+            r: self.r.saturating_sub(other.r),
+            g: self.g.saturating_sub(other.g),
+            b: self.b.saturating_sub(other.b),
+        }
+    }
+
+    /// Adds `amount` to each channel, saturating at 31
+    pub fn lighten(&self, amount: u8) -> Color {
+        Self::new(
+            self.r.saturating_add(amount),
+            self.g.saturating_add(amount),
+            self.b.saturating_add(amount),
+        )
+    }
+
+    /// Subtracts `amount` from each channel, saturating at 0
+    pub fn darken(&self, amount: u8) -> Color {
+        Self::new(
+            self.r.saturating_sub(amount),
+            self.g.saturating_sub(amount),
+            self.b.saturating_sub(amount),
+        )
+    }
+
+    /// Scales each channel by `factor`, rounding and clamping to 0-31
+    pub fn scale(&self, factor: f32) -> Color {
+        let scale = |c: u8| (c as f32 * factor).round().clamp(0.0, 31.0) as u8;
+        Self::new(scale(self.r), scale(self.g), scale(self.b))
+    }
+
+    /// Alpha-blends another color over this one
+    ///
+    /// Computes `self * (1 - alpha) + other * alpha` per channel with rounding.
+    /// `alpha` is clamped to 0.0-1.0, so 0.0 returns `self` and 1.0 `other`
+    pub fn blend(&self, other: &Color, alpha: f32) -> Color {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 * (1.0 - alpha) + b as f32 * alpha).round() as u8;
+        Self::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+
+    /// Linearly interpolates from `a` (t = 0.0) to `b` (t = 1.0)
+    ///
+    /// Same as `a.blend(&b, t)`; `t` is clamped to 0.0-1.0
+    pub fn lerp(a: Color, b: Color, t: f32) -> Color {
+        a.blend(&b, t)
+    }
+
+    /// Returns `steps` evenly spaced colors from `a` to `b`, inclusive
+    ///
+    /// A single step yields just `a`; zero steps yield an empty Vec
+    pub fn gradient(a: Color, b: Color, steps: usize) -> Vec<Color> {
+        match steps {
+            0 => Vec::new(),
+            1 => vec![a],
+            _ => (0..steps)
+                .map(|i| Self::lerp(a, b, i as f32 / (steps - 1) as f32))
+                .collect(),
+        }
+    }
+
     /// Returns the individual RGB components (0-31 range)
     pub fn rgb(&self) -> (u8, u8, u8) {
         (self.r, self.g, self.b)
@@ -89,8 +329,189 @@ impl Default for Color {
     }
 }
 
+impl From<[u8; 3]> for Color {
+    /// Same as `Color::new(r, g, b)`; components are clamped to 0-31
+    fn from([r, g, b]: [u8; 3]) -> Self {
+        Color::new(r, g, b)
+    }
+}
+
+impl From<Color> for [u8; 3] {
+    /// Returns the 5-bit `[r, g, b]` components
+    fn from(color: Color) -> Self {
+        [color.r, color.g, color.b]
+    }
+}
+
+impl Add for Color {
+    type Output = Color;
+
+    /// Channel-wise addition, saturating at 31 (see `saturating_add()`)
+    fn add(self, other: Color) -> Color {
+        self.saturating_add(&other)
+    }
+}
+
+impl Sub for Color {
+    type Output = Color;
+
+    /// Channel-wise subtraction, saturating at 0 (see `saturating_sub()`)
+    fn sub(self, other: Color) -> Color {
+        self.saturating_sub(&other)
+    }
+}
+
+impl Mul<f32> for Color {
+    type Output = Color;
+
+    /// Scales each channel by `factor` (see `scale()`)
+    fn mul(self, factor: f32) -> Color {
+        self.scale(factor)
+    }
+}
+
+/// Type of color vision deficiency simulated by `Palette::colorblind_conflicts()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorBlindness {
+    /// Missing long-wavelength (red) cones
+    Protanopia,
+    /// Missing medium-wavelength (green) cones
+    Deuteranopia,
+    /// Missing short-wavelength (blue) cones
+    Tritanopia,
+}
+
+impl ColorBlindness {
+    /// Simulation matrix applied in linear RGB (Machado et al. 2009, full severity)
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            Self::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Self::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Self::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    /// Returns how `color` appears under this deficiency, as normalized RGB
+    fn simulate(self, color: Color) -> [f32; 3] {
+        let (r, g, b) = color.to_rgb_f32();
+        let linear = [r.powf(2.2), g.powf(2.2), b.powf(2.2)];
+        self.matrix().map(|row| {
+            let value = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+            value.clamp(0.0, 1.0).powf(1.0 / 2.2)
+        })
+    }
+}
+
+/// Normalized RGB distance below which two colors are considered indistinguishable
+const COLORBLIND_THRESHOLD: f32 = 0.05;
+
+/// Converts normalized RGB to HSV with hue in degrees (0-360) and S, V in 0.0-1.0
+fn rgb_to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
+
+/// Converts HSV (hue in degrees, S and V in 0.0-1.0) to normalized RGB
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
+    let c = v * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    (r + m, g + m, b + m)
+}
+
+/// Reduces a set of distinct colors to at most `max` colors using median cut
+///
+/// Repeatedly splits the box with the widest channel range at its median
+/// along that channel, then averages each box. Returns the input unchanged if
+/// it already has at most `max` colors.
+fn median_cut(colors: Vec<Color>, max: usize) -> Vec<Color> {
+    if colors.len() <= max {
+        return colors;
+    }
+
+    let channel = |color: &Color, axis: usize| {
+        let (r, g, b) = color.rgb();
+        [r, g, b][axis]
+    };
+    // Returns (range, axis) of the widest channel in a box
+    let widest = |colors: &[Color]| {
+        (0..3)
+            .map(|axis| {
+                let values = colors.iter().map(|c| channel(c, axis));
+                let range = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+                (range, axis)
+            })
+            .max_by_key(|&(range, axis)| (range, std::cmp::Reverse(axis)))
+            .unwrap_or((0, 0))
+    };
+
+    let mut boxes = vec![colors];
+    while boxes.len() < max {
+        let Some((index, (_, axis))) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| (i, widest(b)))
+            .max_by_key(|&(i, (range, _))| (range, std::cmp::Reverse(i)))
+        else {
+            break;
+        };
+        let mut split = boxes.swap_remove(index);
+        split.sort_by_key(|c| channel(c, axis));
+        let upper = split.split_off(split.len() / 2);
+        boxes.push(split);
+        boxes.push(upper);
+    }
+
+    boxes
+        .iter()
+        .map(|b| {
+            let n = b.len() as u32;
+            let sum = |axis| b.iter().map(|c| channel(c, axis) as u32).sum::<u32>();
+            Color::new(
+                ((sum(0) + n / 2) / n) as u8,
+                ((sum(1) + n / 2) / n) as u8,
+                ((sum(2) + n / 2) / n) as u8,
+            )
+        })
+        .collect()
+}
+
 /// Represents the complete palette with 256 colors organized into 16 sub-palettes
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Palette {
     sub_palettes: [[Color; 16]; 16],
 }
@@ -126,135 +547,751 @@ impl Palette {
         self.sub_palettes[palette_idx as usize % 16][color_idx as usize % 16] = color;
     }
 
-    /// Exports the entire palette as binary data (512 bytes)
+    /// Gets all 16 colors of a sub-palette
     ///
-    /// Format: 256 colors × 2 bytes (RGB555, little-endian)
-    /// Can be directly loaded into Cicada-16 CRAM (F200-F3FF)
-    pub fn export_binary(&self) -> Vec<u8> {
-        let mut data = Vec::with_capacity(512);
-        for sub_palette in &self.sub_palettes {
-            for color in sub_palette {
-                let rgb555 = color.to_rgb555();
-                data.push((rgb555 & 0xFF) as u8); // Low byte
-                data.push(((rgb555 >> 8) & 0xFF) as u8); // High byte
+    /// The index is wrapped modulo 16 if out of bounds
+    pub fn get_sub_palette(&self, palette_idx: u8) -> [Color; 16] {
+        self.sub_palettes[palette_idx as usize % 16]
+    }
+
+    /// Replaces all 16 colors of a sub-palette
+    ///
+    /// The index is wrapped modulo 16 if out of bounds
+    pub fn set_sub_palette(&mut self, palette_idx: u8, colors: &[Color; 16]) {
+        self.sub_palettes[palette_idx as usize % 16] = *colors;
+    }
+
+    /// Exchanges two whole sub-palettes
+    ///
+    /// Indices are wrapped modulo 16 if out of bounds
+    pub fn swap_sub_palettes(&mut self, a: u8, b: u8) {
+        self.sub_palettes.swap(a as usize % 16, b as usize % 16);
+    }
+
+    /// Copies a whole sub-palette over another one
+    ///
+    /// # Arguments
+    /// * `src` - Sub-palette index (0-15) to copy from
+    /// * `dst` - Sub-palette index (0-15) to overwrite
+    ///
+    /// Indices are wrapped modulo 16 if out of bounds
+    pub fn copy_sub_palette(&mut self, src: u8, dst: u8) {
+        self.sub_palettes[dst as usize % 16] = self.sub_palettes[src as usize % 16];
+    }
+
+    /// Applies a color transformation to every color in the given sub-palettes
+    ///
+    /// # Arguments
+    /// * `subs` - Sub-palette indices (0-15) to transform
+    /// * `f` - Transformation applied to each color
+    ///
+    /// Indices are wrapped modulo 16; each sub-palette is transformed at most
+    /// once even if listed repeatedly.
+    pub fn apply_to_subs(&mut self, subs: &[u8], f: impl Fn(Color) -> Color) {
+        let mut selected = [false; 16];
+        for &sub in subs {
+            selected[sub as usize % 16] = true;
+        }
+        for (sub_palette, selected) in self.sub_palettes.iter_mut().zip(selected) {
+            if selected {
+                for color in sub_palette.iter_mut() {
+                    *color = f(*color);
+                }
             }
         }
-        data
     }
 
-    /// Imports a palette from binary data (512 bytes)
+    /// Rotates a range of colors in the flat 256-slot palette space
     ///
-    /// Returns None if data length is not exactly 512 bytes
-    pub fn import_binary(data: &[u8]) -> Option<Self> {
-        if data.len() != 512 {
-            return None;
+    /// # Arguments
+    /// * `start_slot` - First slot of the range (`palette_idx * 16 + color_idx`)
+    /// * `len` - Number of slots in the range (clipped to the end of the palette)
+    /// * `steps` - Slots to rotate by; positive moves colors toward higher slots
+    ///
+    /// The range may cross sub-palette boundaries, which allows color cycling
+    /// effects that span several banks. Colors leaving one end of the range
+    /// wrap around to the other.
+    pub fn rotate_global_range(&mut self, start_slot: usize, len: usize, steps: i8) {
+        let end = start_slot.saturating_add(len).min(256);
+        if start_slot >= end {
+            return;
         }
+        let mut flat: Vec<Color> = self.sub_palettes.iter().flatten().copied().collect();
+        let range = &mut flat[start_slot..end];
+        let shift = (steps as isize).rem_euclid(range.len() as isize) as usize;
+        range.rotate_right(shift);
+        for (slot, color) in flat.into_iter().enumerate() {
+            self.sub_palettes[slot / 16][slot % 16] = color;
+        }
+    }
 
-        let mut palette = Palette::new();
-        for palette_idx in 0..16 {
-            for color_idx in 0..16 {
-                let offset = (palette_idx * 16 + color_idx) * 2;
-                let low = data[offset] as u16;
-                let high = data[offset + 1] as u16;
-                let rgb555 = (high << 8) | low;
-                palette.sub_palettes[palette_idx][color_idx] = Color::from_rgb555(rgb555);
+    /// Finds color pairs in a sub-palette that merge under a color vision deficiency
+    ///
+    /// # Arguments
+    /// * `sub` - Sub-palette index (0-15)
+    /// * `kind` - Deficiency to simulate
+    ///
+    /// Returns `(color_idx_a, color_idx_b)` pairs (with a < b) that are
+    /// distinguishable with normal vision but nearly identical once simulated.
+    pub fn colorblind_conflicts(&self, sub: u8, kind: ColorBlindness) -> Vec<(u8, u8)> {
+        let colors = &self.sub_palettes[sub as usize % 16];
+        let distance = |a: [f32; 3], b: [f32; 3]| {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| (x - y) * (x - y))
+                .sum::<f32>()
+                .sqrt()
+        };
+        let normal: Vec<[f32; 3]> = colors
+            .iter()
+            .map(|c| {
+                let (r, g, b) = c.to_rgb_f32();
+                [r, g, b]
+            })
+            .collect();
+        let simulated: Vec<[f32; 3]> = colors.iter().map(|c| kind.simulate(*c)).collect();
+
+        let mut conflicts = Vec::new();
+        for a in 0..16 {
+            for b in (a + 1)..16 {
+                if distance(normal[a], normal[b]) >= COLORBLIND_THRESHOLD
+                    && distance(simulated[a], simulated[b]) < COLORBLIND_THRESHOLD
+                {
+                    conflicts.push((a as u8, b as u8));
+                }
             }
         }
-
-        Some(palette)
+        conflicts
     }
-}
 
-impl Default for Palette {
-    fn default() -> Self {
-        Self::new()
+    /// Reduces every color channel to `levels` evenly spaced steps across 0-31
+    ///
+    /// `levels` is clamped to 2-32; 32 leaves the palette unchanged and 2
+    /// collapses each channel to 0 or 31.
+    pub fn posterize(&mut self, levels: u8) {
+        let steps = levels.clamp(2, 32) as u32 - 1;
+        let snap = |c: u8| {
+            let level = (c as u32 * steps + 15) / 31; // Nearest step
+            ((level * 31 + steps / 2) / steps) as u8
+        };
+        for color in self.sub_palettes.iter_mut().flatten() {
+            let (r, g, b) = color.rgb();
+            *color = Color::new(snap(r), snap(g), snap(b));
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_color_new() {
-        let color = Color::new(15, 20, 25);
-        assert_eq!(color.rgb(), (15, 20, 25));
+    /// Sorts one sub-palette from dark to light by `Color::luminance()`
+    ///
+    /// The sort is stable, so colors of equal luminance keep their order. The
+    /// sub-palette index wraps around (0-15).
+    ///
+    /// Returns a remap table where `remap[old] == new`; pass it to
+    /// `Tile::remap_colors()` to keep tiles looking the same
+    pub fn sort_sub_palette(&mut self, palette_idx: u8) -> [u8; 16] {
+        self.sort_sub_palette_from(palette_idx, 0)
     }
 
-    #[test]
-    fn test_color_new_clamps() {
-        let color = Color::new(50, 100, 255);
-        assert_eq!(color.rgb(), (31, 31, 31)); // All clamped to max
+    /// Like `sort_sub_palette()`, but leaves color index 0 in place
+    ///
+    /// Useful when index 0 is the transparent or backdrop color
+    pub fn sort_sub_palette_keeping_first(&mut self, palette_idx: u8) -> [u8; 16] {
+        self.sort_sub_palette_from(palette_idx, 1)
     }
 
-    #[test]
-    fn test_color_rgb555_conversion() {
-        let color = Color::new(31, 16, 8);
-        let rgb555 = color.to_rgb555();
-
-        // 31 << 10 | 16 << 5 | 8 = 0b11111_10000_01000
-        assert_eq!(rgb555, 0b11111_10000_01000);
+    /// Sorts color indices `start..16` of a sub-palette by luminance
+    fn sort_sub_palette_from(&mut self, palette_idx: u8, start: usize) -> [u8; 16] {
+        let sub = &mut self.sub_palettes[(palette_idx % 16) as usize];
+        let mut order: Vec<usize> = (start..16).collect();
+        order.sort_by(|&a, &b| sub[a].luminance().total_cmp(&sub[b].luminance()));
 
-        let color2 = Color::from_rgb555(rgb555);
-        assert_eq!(color, color2);
+        let original = *sub;
+        let mut remap: [u8; 16] = std::array::from_fn(|i| i as u8);
+        for (offset, &old) in order.iter().enumerate() {
+            sub[start + offset] = original[old];
+            remap[old] = (start + offset) as u8;
+        }
+        remap
     }
 
-    #[test]
-    fn test_color_rgb555_black() {
-        let color = Color::new(0, 0, 0);
-        assert_eq!(color.to_rgb555(), 0x0000);
+    /// Fills a run of colors with a shading ramp built from one base color
+    ///
+    /// Writes `count` colors starting at `start` in sub-palette `sub`, going
+    /// from a darkened to a lightened version of `base`. Hue and saturation are
+    /// held constant in HSV while value is interpolated, so only brightness
+    /// changes along the ramp. A `count` of 1 writes `base` unchanged. Slots
+    /// past color index 15 are ignored; `sub` wraps like `set_color()`.
+    pub fn fill_shade_ramp(&mut self, sub: u8, start: u8, count: u8, base: Color) {
+        let (r, g, b) = base.to_rgb_f32();
+        let (hue, saturation, value) = rgb_to_hsv(r, g, b);
+        let darkest = value * 0.4;
+        let lightest = value + (1.0 - value) * 0.6;
 
-        let color2 = Color::from_rgb555(0x0000);
-        assert_eq!(color, color2);
+        let count = count.min(16u8.saturating_sub(start));
+        for i in 0..count {
+            let color = if count == 1 {
+                base
+            } else {
+                let t = i as f32 / (count - 1) as f32;
+                let (r, g, b) = hsv_to_rgb(hue, saturation, darkest + (lightest - darkest) * t);
+                Color::from_rgb_f32(r, g, b)
+            };
+            self.set_color(sub, start + i, color);
+        }
     }
 
-    #[test]
-    fn test_color_rgb555_white() {
-        let color = Color::new(31, 31, 31);
-        assert_eq!(color.to_rgb555(), 0x7FFF);
+    /// Quantizes opaque sprite colors into a sub-palette, keeping index 0 transparent
+    ///
+    /// # Arguments
+    /// * `sub` - Sub-palette index (0-15) to write
+    /// * `colors` - Opaque RGB888 colors, e.g. one per sprite pixel
+    ///
+    /// Colors are first reduced to 5-bit with `Color::from_rgb888_rounded()`.
+    /// If at most 15 distinct colors remain they are stored as-is, otherwise
+    /// they are reduced to 15 with median cut. The result fills color indices
+    /// 1-15 (unused slots are left unchanged) and index 0 is never written or
+    /// assigned. Each input maps to the nearest stored color by
+    /// `Color::distance_squared()`, as in `find_nearest()`.
+    ///
+    /// Returns the assigned color index (1-15) for each input color, in order
+    pub fn quantize_sprite(&mut self, sub: u8, colors: &[(u8, u8, u8)]) -> Vec<u8> {
+        let mut unique: Vec<Color> = colors
+            .iter()
+            .map(|&(r, g, b)| Color::from_rgb888_rounded(r, g, b))
+            .collect();
+        unique.sort_by_key(Color::to_rgb555);
+        unique.dedup();
+        let reduced = median_cut(unique, 15);
 
-        let color2 = Color::from_rgb555(0x7FFF);
-        assert_eq!(color, color2);
-    }
+        for (i, color) in reduced.iter().enumerate() {
+            self.set_color(sub, i as u8 + 1, *color);
+        }
 
-    #[test]
-    fn test_color_rgb888_conversion() {
-        let color = Color::new(31, 0, 0); // Max red
-        let (r, g, b) = color.to_rgb888();
+        colors
+            .iter()
+            .map(|&(r, g, b)| {
+                let color = Color::from_rgb888_rounded(r, g, b);
+                let nearest = reduced
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, candidate)| candidate.distance_squared(&color))
+                    .map_or(0, |(i, _)| i);
+                nearest as u8 + 1
+            })
+            .collect()
+    }
 
-        // 31 << 3 | 31 >> 2 = 248 | 7 = 255
-        assert_eq!(r, 255);
-        assert_eq!(g, 0);
-        assert_eq!(b, 0);
+    /// Finds the palette slot closest to a color
+    ///
+    /// Scans all 256 entries using `Color::distance_squared()`. Ties resolve
+    /// to the lowest sub-palette, then the lowest color index.
+    ///
+    /// Returns: `(palette_idx, color_idx)`
+    pub fn find_nearest(&self, target: Color) -> (u8, u8) {
+        let index = self
+            .sub_palettes
+            .iter()
+            .flatten()
+            .enumerate()
+            .min_by_key(|(_, color)| color.distance_squared(&target))
+            .map_or(0, |(i, _)| i);
+        ((index / 16) as u8, (index % 16) as u8)
     }
 
-    #[test]
-    fn test_color_rgb888_round_trip() {
-        // Note: RGB888 -> RGB555 -> RGB888 may not be exact due to precision loss
-        let color = Color::from_rgb888(255, 128, 64);
-        assert_eq!(color.rgb(), (31, 16, 8)); // 255>>3=31, 128>>3=16, 64>>3=8
+    /// Finds the color index (0-15) closest to a color within one sub-palette
+    ///
+    /// Like `find_nearest()`; the sub-palette index wraps around (0-15) and
+    /// ties resolve to the lowest color index
+    pub fn find_nearest_in_sub(&self, palette_idx: u8, target: Color) -> u8 {
+        self.sub_palettes[(palette_idx % 16) as usize]
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, color)| color.distance_squared(&target))
+            .map_or(0, |(i, _)| i as u8)
     }
 
-    #[test]
-    fn test_color_default() {
-        let color = Color::default();
-        assert_eq!(color.rgb(), (0, 0, 0));
+    /// Maps each pixel of an RGBA image to its nearest palette slot
+    ///
+    /// # Arguments
+    /// * `rgba` - RGBA8888 image data (`width * height * 4` bytes)
+    /// * `width` - Image width in pixels
+    /// * `height` - Image height in pixels
+    ///
+    /// Pixels are reduced to 5 bits with `Color::from_rgb888_rounded()` and matched
+    /// with `find_nearest()`; alpha is ignored. Each output byte is the
+    /// flattened slot `palette_idx * 16 + color_idx`, in row-major order.
+    /// Returns an empty Vec if `rgba` has the wrong length or
+    /// `width * height * 4` overflows.
+    pub fn quantize_indices(&self, rgba: &[u8], width: usize, height: usize) -> Vec<u8> {
+        let expected = width.checked_mul(height).and_then(|n| n.checked_mul(4));
+        if expected != Some(rgba.len()) {
+            return Vec::new();
+        }
+        rgba.chunks_exact(4)
+            .map(|px| {
+                let (palette_idx, color_idx) =
+                    self.find_nearest(Color::from_rgb888_rounded(px[0], px[1], px[2]));
+                palette_idx * 16 + color_idx
+            })
+            .collect()
     }
 
-    #[test]
-    fn test_palette_new() {
-        let palette = Palette::new();
+    /// Builds a lookup table for 50/50 blending between two sub-palettes
+    ///
+    /// # Arguments
+    /// * `sub_a` - Sub-palette of the first color, also the target palette
+    /// * `sub_b` - Sub-palette of the second color
+    ///
+    /// `table[a][b]` is the color index in `sub_a` nearest to the average of
+    /// `sub_a[a]` and `sub_b[b]` (per 5-bit channel, rounded up), as found by
+    /// `find_nearest_in_sub()`. Ties go to the lowest index. Useful for emulating translucency on hardware that
+    /// only blends through precomputed tables.
+    pub fn build_blend_table(&self, sub_a: u8, sub_b: u8) -> [[u8; 16]; 16] {
+        let target = &self.sub_palettes[(sub_a % 16) as usize];
+        let other = &self.sub_palettes[(sub_b % 16) as usize];
+        let average = |a: u8, b: u8| (a + b).div_ceil(2);
 
-        // All colors should be black by default
-        for p in 0..16 {
-            for c in 0..16 {
-                assert_eq!(palette.get_color(p, c), Color::default());
+        let mut table = [[0u8; 16]; 16];
+        for (a, row) in table.iter_mut().enumerate() {
+            for (b, slot) in row.iter_mut().enumerate() {
+                let (ar, ag, ab) = target[a].rgb();
+                let (br, bg, bb) = other[b].rgb();
+                let mix = Color::new(average(ar, br), average(ag, bg), average(ab, bb));
+                *slot = self.find_nearest_in_sub(sub_a, mix);
             }
         }
+        table
     }
 
-    #[test]
-    fn test_palette_set_and_get() {
+    /// Imports a palette from JASC-PAL text (Paint Shop Pro, Aseprite)
+    ///
+    /// Expects the `JASC-PAL` and `0100` header lines, a color count, then one
+    /// `R G B` line (8-bit values) per color; any further values on a line,
+    /// such as alpha, are ignored. Colors are converted with
+    /// `Color::from_rgb888_rounded()` and stored 16 per sub-palette in order.
+    /// Colors beyond 256 are ignored and missing slots are left black.
+    ///
+    /// Returns None if the header is malformed, a color line is invalid, or
+    /// there are fewer color lines than the count
+    pub fn from_jasc_pal(text: &str) -> Option<Palette> {
+        let mut lines = text.lines().map(str::trim);
+        if lines.next()? != "JASC-PAL" || lines.next()? != "0100" {
+            return None;
+        }
+        let count: usize = lines.next()?.parse().ok()?;
+
+        let mut palette = Palette::new();
+        for slot in 0..count {
+            let mut values = lines.next()?.split_whitespace().map(str::parse::<u8>);
+            let r = values.next()?.ok()?;
+            let g = values.next()?.ok()?;
+            let b = values.next()?.ok()?;
+            if slot < 256 {
+                palette.sub_palettes[slot / 16][slot % 16] = Color::from_rgb888_rounded(r, g, b);
+            }
+        }
+        Some(palette)
+    }
+
+    /// Exports the palette as JASC-PAL text
+    ///
+    /// Writes the `JASC-PAL`, `0100` and `256` header lines followed by one
+    /// `R G B` line per color, using the `Color::to_rgb888()` expansion, in
+    /// slot order (sub-palette 0 first)
+    pub fn to_jasc_pal(&self) -> String {
+        let mut text = String::from("JASC-PAL\n0100\n256\n");
+        for color in self.sub_palettes.iter().flatten() {
+            let (r, g, b) = color.to_rgb888();
+            text.push_str(&format!("{} {} {}\n", r, g, b));
+        }
+        text
+    }
+
+    /// Imports a palette from GIMP palette (`.gpl`) text
+    ///
+    /// The first non-empty line must be `GIMP Palette`. `Name:` and `Columns:`
+    /// lines, comments (`#`) and blank lines are skipped; every other line is
+    /// an `R G B` row (8-bit values) optionally followed by a color name.
+    /// Colors are converted with `Color::from_rgb888_rounded()` and stored 16
+    /// per sub-palette in order. Colors beyond 256 are ignored and missing
+    /// slots are left black.
+    ///
+    /// Returns None if the header is missing or a color row is invalid
+    pub fn from_gimp_gpl(text: &str) -> Option<Palette> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next()? != "GIMP Palette" {
+            return None;
+        }
+
+        let mut palette = Palette::new();
+        let rows = lines.filter(|line| {
+            !line.starts_with('#') && !line.starts_with("Name:") && !line.starts_with("Columns:")
+        });
+        for (slot, row) in rows.enumerate() {
+            let mut values = row.split_whitespace().map(str::parse::<u8>);
+            let r = values.next()?.ok()?;
+            let g = values.next()?.ok()?;
+            let b = values.next()?.ok()?;
+            if slot < 256 {
+                palette.sub_palettes[slot / 16][slot % 16] = Color::from_rgb888_rounded(r, g, b);
+            }
+        }
+        Some(palette)
+    }
+
+    /// Imports a palette from the PLTE chunk of an indexed PNG
+    ///
+    /// Loads up to 256 colors in order, 16 per sub-palette, quantized to 5-bit
+    /// via `Color::from_rgb888()`. Pixel data is ignored and any slots beyond
+    /// the PNG palette's length are left black.
+    ///
+    /// Returns `SemitileError::Parse` if the PNG cannot be decoded or has no PLTE chunk
+    #[cfg(feature = "png")]
+    pub fn from_png_palette(bytes: &[u8]) -> Result<Palette, SemitileError> {
+        let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        let reader = decoder
+            .read_info()
+            .map_err(|err| SemitileError::Parse(format!("failed to decode PNG: {}", err)))?;
+        let plte = reader
+            .info()
+            .palette
+            .as_ref()
+            .ok_or_else(|| SemitileError::Parse("PNG has no PLTE chunk".to_string()))?;
+
+        let mut palette = Palette::new();
+        for (slot, rgb) in plte.chunks_exact(3).take(256).enumerate() {
+            palette.sub_palettes[slot / 16][slot % 16] = Color::from_rgb888(rgb[0], rgb[1], rgb[2]);
+        }
+        Ok(palette)
+    }
+
+    /// Renders the palette as a 16×16 grid of swatches
+    ///
+    /// Each color is a `cell` × `cell` block (minimum 1) of opaque RGBA8888
+    /// pixels from its `to_rgb888()` expansion. The sub-palette index selects
+    /// the grid row and the color index the column.
+    ///
+    /// Returns: `(rgba, width, height)`, both dimensions being `16 * cell`
+    pub fn to_swatch_rgba(&self, cell: usize) -> (Vec<u8>, usize, usize) {
+        let cell = cell.max(1);
+        let size = 16 * cell;
+
+        let mut rgba = Vec::with_capacity(size * size * 4);
+        for sub in &self.sub_palettes {
+            let mut row = Vec::with_capacity(size * 4);
+            for color in sub {
+                let (r, g, b) = color.to_rgb888();
+                for _ in 0..cell {
+                    row.extend_from_slice(&[r, g, b, 255]);
+                }
+            }
+            for _ in 0..cell {
+                rgba.extend_from_slice(&row);
+            }
+        }
+        (rgba, size, size)
+    }
+
+    /// Renders the palette as a PNG contact sheet for documentation
+    ///
+    /// Each sub-palette is one row of 16 square swatches of `cell` pixels
+    /// (minimum 1), preceded by a label strip that shows the row's index in a
+    /// 3×5 pixel font scaled by `s = max(1, cell / 6)`. The RGB image is
+    /// `8 * s + 16 * cell` wide and `16 * cell` tall; digits are cut off in
+    /// rows shorter than 5 pixels. Rows are also labeled with `tEXt` chunks
+    /// keyed "Sub-palette N" that list the row's colors as RGB555 hex values.
+    ///
+    /// Returns `SemitileError::Encode` if the PNG could not be encoded
+    #[cfg(feature = "png")]
+    pub fn to_contact_sheet_png(&self, cell: usize) -> Result<Vec<u8>, SemitileError> {
+        let cell = cell.max(1);
+        let (rgba, size, _) = self.to_swatch_rgba(cell);
+        let scale = (cell / 6).max(1);
+        let label_width = 8 * scale;
+        let width = label_width + size;
+        let top = cell.saturating_sub(5 * scale) / 2;
+
+        let mut pixels = Vec::with_capacity(width * size * 3);
+        for (y, swatch_row) in rgba.chunks_exact(size * 4).enumerate() {
+            let sub = y / cell;
+            let digits = if sub >= 10 {
+                [Some(sub / 10), Some(sub % 10)]
+            } else {
+                [Some(sub), None]
+            };
+            let glyph_line = (y % cell)
+                .checked_sub(top)
+                .map(|dy| dy / scale)
+                .filter(|&line| line < 5);
+            // Columns: 1 padding, 3 for the first digit, 1 gap, 3 for the second
+            for x in 0..label_width {
+                let lit = glyph_line.is_some_and(|line| {
+                    let (slot, bit) = match x / scale {
+                        col @ 1..=3 => (0, col - 1),
+                        col @ 5..=7 => (1, col - 5),
+                        _ => return false,
+                    };
+                    digits[slot].is_some_and(|d| DIGIT_GLYPHS[d][line] & (0b100 >> bit) != 0)
+                });
+                pixels.extend_from_slice(&LABEL_COLORS[lit as usize]);
+            }
+            pixels.extend(swatch_row.chunks_exact(4).flat_map(|px| &px[..3]));
+        }
+
+        let mut bytes = Vec::new();
+        let encode = |bytes: &mut Vec<u8>| -> Result<(), png::EncodingError> {
+            let mut encoder = png::Encoder::new(bytes, width as u32, size as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            for (index, sub) in self.sub_palettes.iter().enumerate() {
+                let values: Vec<String> = sub
+                    .iter()
+                    .map(|color| format!("0x{:04X}", color.to_rgb555()))
+                    .collect();
+                encoder.add_text_chunk(format!("Sub-palette {}", index), values.join(" "))?;
+            }
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&pixels)?;
+            writer.finish()
+        };
+        encode(&mut bytes)
+            .map_err(|err| SemitileError::Encode(format!("failed to encode PNG: {}", err)))?;
+        Ok(bytes)
+    }
+
+    /// Returns every color packed as RGB555 in CRAM order
+    ///
+    /// Slot `palette_idx * 16 + color_idx` holds that color's `to_rgb555()`
+    /// value. Comparing or hashing the packed words is cheaper than comparing
+    /// palettes color by color, which makes this useful for caching.
+    pub fn as_rgb555_array(&self) -> [u16; 256] {
+        let mut packed = [0u16; 256];
+        for (value, color) in packed.iter_mut().zip(self.sub_palettes.iter().flatten()) {
+            *value = color.to_rgb555();
+        }
+        packed
+    }
+
+    /// Counts the distinct RGB555 values across all 256 slots
+    ///
+    /// Duplicates such as unused black slots count once, so a fresh palette
+    /// returns 1
+    pub fn unique_color_count(&self) -> usize {
+        self.sub_palettes
+            .iter()
+            .flatten()
+            .map(Color::to_rgb555)
+            .collect::<HashSet<u16>>()
+            .len()
+    }
+
+    /// Exports the entire palette as binary data (512 bytes)
+    ///
+    /// Format: 256 colors × 2 bytes (RGB555, little-endian)
+    /// Can be directly loaded into Cicada-16 CRAM (F200-F3FF)
+    pub fn export_binary(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(512);
+        for sub_palette in &self.sub_palettes {
+            for color in sub_palette {
+                let rgb555 = color.to_rgb555();
+                data.push((rgb555 & 0xFF) as u8); // Low byte
+                data.push(((rgb555 >> 8) & 0xFF) as u8); // High byte
+            }
+        }
+        data
+    }
+
+    /// Exports the palette as Rust source for embedding in builds
+    ///
+    /// Emits `pub const <const_name>: [u16; 256] = [...];` holding the RGB555
+    /// value of every color in CRAM order, 16 values per line
+    pub fn export_rust(&self, const_name: &str) -> String {
+        let mut out = format!("pub const {}: [u16; 256] = [\n", const_name);
+        for sub_palette in &self.sub_palettes {
+            let values: Vec<String> = sub_palette
+                .iter()
+                .map(|color| format!("0x{:04X}", color.to_rgb555()))
+                .collect();
+            out.push_str(&format!("    {},\n", values.join(", ")));
+        }
+        out.push_str("];\n");
+        out
+    }
+
+    /// Imports a palette from binary data (512 bytes)
+    ///
+    /// Returns None if data length is not exactly 512 bytes
+    pub fn import_binary(data: &[u8]) -> Option<Self> {
+        if data.len() != 512 {
+            return None;
+        }
+
+        let mut palette = Palette::new();
+        for palette_idx in 0..16 {
+            for color_idx in 0..16 {
+                let offset = (palette_idx * 16 + color_idx) * 2;
+                let low = data[offset] as u16;
+                let high = data[offset + 1] as u16;
+                let rgb555 = (high << 8) | low;
+                palette.sub_palettes[palette_idx][color_idx] = Color::from_rgb555(rgb555);
+            }
+        }
+
+        Some(palette)
+    }
+
+    /// Imports selected colors from binary data (512 bytes), keeping the rest
+    ///
+    /// # Arguments
+    /// * `data` - Palette binary in the `export_binary()` format
+    /// * `mask` - One flag per slot (`palette_idx * 16 + color_idx`); only slots
+    ///   flagged true are overwritten
+    ///
+    /// Returns `SemitileError::InvalidLength` and leaves the palette unchanged
+    /// if data length is not exactly 512 bytes
+    pub fn import_binary_masked(
+        &mut self,
+        data: &[u8],
+        mask: &[bool; 256],
+    ) -> Result<(), SemitileError> {
+        let imported = Palette::import_binary(data).ok_or(SemitileError::InvalidLength {
+            expected: 512,
+            actual: data.len(),
+        })?;
+        let slots = self.sub_palettes.iter_mut().flatten();
+        for ((slot, color), &selected) in
+            slots.zip(imported.sub_palettes.iter().flatten()).zip(mask)
+        {
+            if selected {
+                *slot = *color;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Previews how an RGBA8888 image looks after snapping to a palette
+///
+/// # Arguments
+/// * `pixels` - RGBA8888 image data (`width * height * 4` bytes)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `palette` - Palette whose 256 colors are candidates for every pixel
+///
+/// Each pixel is replaced by the RGB888 expansion of the palette color that
+/// `Palette::quantize_indices()` would pick for it, with alpha passed through
+/// unchanged. Unlike a full import, the image is not sliced into tiles.
+/// Returns an empty Vec if `pixels` has the wrong length or
+/// `width * height * 4` overflows.
+pub fn preview_quantized_rgba(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    palette: &Palette,
+) -> Vec<u8> {
+    let expected = width.checked_mul(height).and_then(|n| n.checked_mul(4));
+    if expected != Some(pixels.len()) {
+        return Vec::new();
+    }
+
+    let indices = palette.quantize_indices(pixels, width, height);
+    let mut out = Vec::with_capacity(pixels.len());
+    for (px, &index) in pixels.chunks_exact(4).zip(&indices) {
+        let (r, g, b) = palette.get_color(index / 16, index % 16).to_rgb888();
+        out.extend_from_slice(&[r, g, b, px[3]]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_new() {
+        let color = Color::new(15, 20, 25);
+        assert_eq!(color.rgb(), (15, 20, 25));
+    }
+
+    #[test]
+    fn test_color_new_clamps() {
+        let color = Color::new(50, 100, 255);
+        assert_eq!(color.rgb(), (31, 31, 31)); // All clamped to max
+    }
+
+    #[test]
+    fn test_color_rgb555_conversion() {
+        let color = Color::new(31, 16, 8);
+        let rgb555 = color.to_rgb555();
+
+        // 31 << 10 | 16 << 5 | 8 = 0b11111_10000_01000
+        assert_eq!(rgb555, 0b11111_10000_01000);
+
+        let color2 = Color::from_rgb555(rgb555);
+        assert_eq!(color, color2);
+    }
+
+    #[test]
+    fn test_color_rgb555_black() {
+        let color = Color::new(0, 0, 0);
+        assert_eq!(color.to_rgb555(), 0x0000);
+
+        let color2 = Color::from_rgb555(0x0000);
+        assert_eq!(color, color2);
+    }
+
+    #[test]
+    fn test_color_rgb555_white() {
+        let color = Color::new(31, 31, 31);
+        assert_eq!(color.to_rgb555(), 0x7FFF);
+
+        let color2 = Color::from_rgb555(0x7FFF);
+        assert_eq!(color, color2);
+    }
+
+    #[test]
+    fn test_color_rgb888_conversion() {
+        let color = Color::new(31, 0, 0); // Max red
+        let (r, g, b) = color.to_rgb888();
+
+        // 31 << 3 | 31 >> 2 = 248 | 7 = 255
+        assert_eq!(r, 255);
+        assert_eq!(g, 0);
+        assert_eq!(b, 0);
+    }
+
+    #[test]
+    fn test_color_rgb888_round_trip() {
+        // Note: RGB888 -> RGB555 -> RGB888 may not be exact due to precision loss
+        let color = Color::from_rgb888(255, 128, 64);
+        assert_eq!(color.rgb(), (31, 16, 8)); // 255>>3=31, 128>>3=16, 64>>3=8
+    }
+
+    #[test]
+    fn test_color_default() {
+        let color = Color::default();
+        assert_eq!(color.rgb(), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_palette_new() {
+        let palette = Palette::new();
+
+        // All colors should be black by default
+        for p in 0..16 {
+            for c in 0..16 {
+                assert_eq!(palette.get_color(p, c), Color::default());
+            }
+        }
+    }
+
+    #[test]
+    fn test_palette_set_and_get() {
         let mut palette = Palette::new();
         let color = Color::new(15, 20, 25);
 
@@ -349,4 +1386,908 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_color_rgb_f32_conversion() {
+        let color = Color::from_rgb_f32(1.0, 0.0, 0.5);
+        assert_eq!(color.rgb(), (31, 0, 16)); // 0.5 * 31 = 15.5 rounds to 16
+
+        // Out-of-range inputs are clamped
+        let color = Color::from_rgb_f32(2.0, -1.0, 0.0);
+        assert_eq!(color.rgb(), (31, 0, 0));
+
+        // Round-trip stays within one quantization step
+        for (r, g, b) in [(0.1, 0.42, 0.9), (0.33, 0.66, 0.99)] {
+            let (r2, g2, b2) = Color::from_rgb_f32(r, g, b).to_rgb_f32();
+            let step = 0.5 / 31.0 + f32::EPSILON;
+            assert!((r - r2).abs() <= step);
+            assert!((g - g2).abs() <= step);
+            assert!((b - b2).abs() <= step);
+        }
+    }
+
+    #[test]
+    fn test_preview_quantized_rgba_exact_colors_unchanged() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 1, Color::new(31, 0, 0));
+        palette.set_color(2, 5, Color::new(0, 31, 0));
+        palette.set_color(7, 15, Color::new(10, 20, 30));
+
+        let mut pixels = Vec::new();
+        for (p, c, a) in [(0, 1, 255), (2, 5, 128), (7, 15, 255), (0, 0, 0)] {
+            let (r, g, b) = palette.get_color(p, c).to_rgb888();
+            pixels.extend_from_slice(&[r, g, b, a]);
+        }
+
+        let preview = preview_quantized_rgba(&pixels, 2, 2, &palette);
+        assert_eq!(preview, pixels);
+    }
+
+    #[test]
+    fn test_preview_quantized_rgba_snaps_and_validates() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 1, Color::new(31, 31, 31));
+
+        // Light gray snaps to white, dark gray to black
+        let pixels = [200, 200, 200, 255, 40, 40, 40, 255];
+        let preview = preview_quantized_rgba(&pixels, 2, 1, &palette);
+        assert_eq!(preview, vec![255, 255, 255, 255, 0, 0, 0, 255]);
+
+        // Wrong length yields an empty preview
+        assert!(preview_quantized_rgba(&pixels, 3, 1, &palette).is_empty());
+        assert!(preview_quantized_rgba(&pixels, usize::MAX, 2, &palette).is_empty());
+    }
+
+    #[test]
+    fn test_palette_posterize_two_levels() {
+        let mut palette = Palette::new();
+        for p in 0..16 {
+            for c in 0..16 {
+                palette.set_color(p, c, Color::new(p * 2, c * 2, (p + c) % 32));
+            }
+        }
+
+        palette.posterize(2);
+        for p in 0..16 {
+            for c in 0..16 {
+                let (r, g, b) = palette.get_color(p, c).rgb();
+                for channel in [r, g, b] {
+                    assert!(channel == 0 || channel == 31, "got {}", channel);
+                }
+            }
+        }
+        assert_eq!(palette.get_color(0, 7).rgb(), (0, 0, 0)); // 14 rounds down
+        assert_eq!(palette.get_color(0, 8).rgb(), (0, 31, 0)); // 16 rounds up
+    }
+
+    #[test]
+    fn test_palette_posterize_full_levels_is_noop() {
+        let mut palette = Palette::new();
+        palette.set_color(1, 1, Color::new(3, 17, 29));
+        let original = palette.clone();
+
+        palette.posterize(32);
+        assert_eq!(palette, original);
+
+        // Four levels snap to 0, 10, 21, 31
+        palette.posterize(4);
+        assert_eq!(palette.get_color(1, 1).rgb(), (0, 21, 31));
+    }
+
+    #[test]
+    fn test_palette_apply_to_subs() {
+        let mut palette = Palette::new();
+        for p in 0..3 {
+            palette.set_color(p, 4, Color::new(1, 2, 3));
+        }
+        let invert = |c: Color| {
+            let (r, g, b) = c.rgb();
+            Color::new(31 - r, 31 - g, 31 - b)
+        };
+
+        // Sub 0 listed twice must only be inverted once
+        palette.apply_to_subs(&[0, 1, 0], invert);
+
+        assert_eq!(palette.get_color(0, 4).rgb(), (30, 29, 28));
+        assert_eq!(palette.get_color(1, 4).rgb(), (30, 29, 28));
+        assert_eq!(palette.get_color(1, 0).rgb(), (31, 31, 31));
+        assert_eq!(palette.get_color(2, 4).rgb(), (1, 2, 3));
+        assert_eq!(palette.get_color(2, 0), Color::default());
+    }
+
+    #[cfg(feature = "png")]
+    fn encode_test_png(color_type: png::ColorType, palette: Option<&[u8]>, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        if let Some(palette) = palette {
+            encoder.set_palette(palette.to_vec());
+        }
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_palette_from_png_palette() {
+        let mut plte = vec![255, 0, 0, 0, 255, 0, 0, 0, 255];
+        plte.resize(17 * 3, 0);
+        plte.extend_from_slice(&[255, 255, 255]); // Slot 17 = sub-palette 1, color 1
+        let bytes = encode_test_png(png::ColorType::Indexed, Some(&plte), &[0, 1]);
+
+        let palette = Palette::from_png_palette(&bytes).unwrap();
+        assert_eq!(palette.get_color(0, 0).rgb(), (31, 0, 0));
+        assert_eq!(palette.get_color(0, 1).rgb(), (0, 31, 0));
+        assert_eq!(palette.get_color(0, 2).rgb(), (0, 0, 31));
+        assert_eq!(palette.get_color(1, 1).rgb(), (31, 31, 31));
+        assert_eq!(palette.get_color(5, 5), Color::default());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_palette_from_png_palette_errors() {
+        let bytes = encode_test_png(png::ColorType::Grayscale, None, &[0, 255]);
+        assert_eq!(
+            Palette::from_png_palette(&bytes),
+            Err(SemitileError::Parse("PNG has no PLTE chunk".to_string()))
+        );
+        assert!(matches!(
+            Palette::from_png_palette(b"not a png"),
+            Err(SemitileError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_color_add_sub_saturate() {
+        let a = Color::new(20, 5, 31);
+        let b = Color::new(15, 3, 1);
+
+        assert_eq!((a + b).rgb(), (31, 8, 31));
+        assert_eq!((b - a).rgb(), (0, 0, 0));
+        assert_eq!((a - b).rgb(), (5, 2, 30));
+        assert_eq!(a + b, a.saturating_add(&b));
+        assert_eq!(a - b, a.saturating_sub(&b));
+    }
+
+    #[test]
+    fn test_color_mul_rounds_and_clamps() {
+        let color = Color::new(31, 15, 3);
+        assert_eq!((color * 0.5).rgb(), (16, 8, 2)); // 15.5, 7.5, 1.5 round away from zero
+        assert_eq!((color * 2.0).rgb(), (31, 30, 6));
+        assert_eq!((color * -1.0).rgb(), (0, 0, 0));
+        assert_eq!(color * 1.0, color);
+    }
+
+    #[test]
+    fn test_palette_rotate_global_range_across_banks() {
+        let mut palette = Palette::new();
+        // Slots 14..18 span sub-palette 0 (colors 14, 15) and 1 (colors 0, 1)
+        let colors = [
+            Color::new(1, 0, 0),
+            Color::new(2, 0, 0),
+            Color::new(3, 0, 0),
+            Color::new(4, 0, 0),
+        ];
+        for (i, color) in colors.iter().enumerate() {
+            let slot = 14 + i;
+            palette.set_color((slot / 16) as u8, (slot % 16) as u8, *color);
+        }
+        palette.set_color(1, 2, Color::new(9, 9, 9)); // Outside the range
+
+        palette.rotate_global_range(14, 4, 1);
+        assert_eq!(palette.get_color(0, 14), colors[3]); // Wrapped from the end
+        assert_eq!(palette.get_color(0, 15), colors[0]);
+        assert_eq!(palette.get_color(1, 0), colors[1]);
+        assert_eq!(palette.get_color(1, 1), colors[2]);
+        assert_eq!(palette.get_color(1, 2), Color::new(9, 9, 9));
+
+        // Negative steps rotate back
+        palette.rotate_global_range(14, 4, -1);
+        assert_eq!(palette.get_color(0, 14), colors[0]);
+        assert_eq!(palette.get_color(1, 1), colors[3]);
+    }
+
+    #[test]
+    fn test_palette_export_rust() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 1, Color::new(31, 0, 0));
+        palette.set_color(15, 15, Color::new(1, 2, 3));
+
+        let source = palette.export_rust("GAME_PALETTE");
+        assert!(source.starts_with("pub const GAME_PALETTE: [u16; 256] = [\n"));
+        assert!(source.ends_with("];\n"));
+
+        // Parse the emitted literals back into colors
+        let values: Vec<u16> = source
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter_map(|token| token.strip_prefix("0x"))
+            .map(|hex| u16::from_str_radix(hex, 16).unwrap())
+            .collect();
+        assert_eq!(values.len(), 256);
+        for (slot, value) in values.iter().enumerate() {
+            let expected = palette.get_color((slot / 16) as u8, (slot % 16) as u8);
+            assert_eq!(Color::from_rgb555(*value), expected);
+        }
+    }
+
+    #[test]
+    fn test_palette_colorblind_conflicts() {
+        let mut palette = Palette::new();
+        // Classic red/green confusion pair, plus a blue that stays distinct
+        palette.set_color(2, 0, Color::new(31, 31, 31));
+        palette.set_color(2, 1, Color::new(31, 0, 0));
+        palette.set_color(2, 2, Color::new(0, 13, 0));
+        palette.set_color(2, 3, Color::new(0, 0, 31));
+        for c in 4..16 {
+            palette.set_color(2, c, Color::new(31, 31, 31)); // Duplicates are never reported
+        }
+
+        let conflicts = palette.colorblind_conflicts(2, ColorBlindness::Protanopia);
+        assert_eq!(conflicts, vec![(1, 2)]);
+
+        // Same pair stays distinguishable without red/green confusion
+        let conflicts = palette.colorblind_conflicts(2, ColorBlindness::Tritanopia);
+        assert!(!conflicts.contains(&(1, 2)));
+    }
+
+    #[test]
+    fn test_palette_as_rgb555_array() {
+        let mut palette1 = Palette::new();
+        palette1.set_color(3, 4, Color::new(31, 16, 8));
+        let mut palette2 = palette1.clone();
+
+        let packed = palette1.as_rgb555_array();
+        assert_eq!(packed, palette2.as_rgb555_array());
+        assert_eq!(packed[3 * 16 + 4], 0b11111_10000_01000);
+
+        palette2.set_color(15, 15, Color::new(1, 0, 0));
+        assert_ne!(palette1.as_rgb555_array(), palette2.as_rgb555_array());
+    }
+
+    #[test]
+    fn test_color_yuv_grayscale_has_no_chroma() {
+        for level in [0, 7, 16, 31] {
+            let (y, u, v) = Color::new(level, level, level).to_yuv();
+            assert!((y - level as f32 / 31.0).abs() < 1e-3);
+            assert!(u.abs() < 1e-3, "u = {}", u);
+            assert!(v.abs() < 1e-3, "v = {}", v);
+        }
+    }
+
+    #[test]
+    fn test_color_yuv_round_trip() {
+        for (r, g, b) in [
+            (31, 0, 0),
+            (0, 31, 0),
+            (0, 0, 31),
+            (12, 25, 3),
+            (31, 31, 31),
+        ] {
+            let color = Color::new(r, g, b);
+            let (y, u, v) = color.to_yuv();
+            let (r2, g2, b2) = Color::from_yuv(y, u, v).rgb();
+            assert!(r.abs_diff(r2) <= 1 && g.abs_diff(g2) <= 1 && b.abs_diff(b2) <= 1);
+        }
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_palette_to_contact_sheet_png() {
+        let mut palette = Palette::new();
+        palette.set_color(2, 3, Color::new(31, 0, 0));
+
+        let bytes = palette.to_contact_sheet_png(4).unwrap();
+        let mut reader = png::Decoder::new(std::io::Cursor::new(&bytes[..]))
+            .read_info()
+            .unwrap();
+        assert_eq!(reader.info().width, 8 + 16 * 4);
+        assert_eq!(reader.info().height, 16 * 4);
+        let label = reader
+            .info()
+            .uncompressed_latin1_text
+            .iter()
+            .find(|chunk| chunk.keyword == "Sub-palette 2")
+            .unwrap();
+        assert!(label.text.contains("0x7C00"));
+
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut pixels).unwrap();
+        // Row 2, column 3 starts at (8 + 12, 8)
+        let pixel = |x: usize, y: usize| &pixels[(y * 72 + x) * 3..(y * 72 + x) * 3 + 3];
+        assert_eq!(pixel(20, 8), &[255, 0, 0]);
+        // Row 1's label "1" has its top stroke in the middle column only
+        assert_eq!(pixel(2, 4), &LABEL_COLORS[1]);
+        assert_eq!(pixel(1, 4), &LABEL_COLORS[0]);
+        // Row 12's label has two digits
+        assert_eq!(pixel(6, 48), &LABEL_COLORS[1]);
+    }
+
+    #[test]
+    fn test_palette_fill_shade_ramp() {
+        let base = Color::new(28, 14, 4);
+        let (r, g, b) = base.to_rgb_f32();
+        let (base_hue, _, _) = rgb_to_hsv(r, g, b);
+
+        let mut palette = Palette::new();
+        palette.fill_shade_ramp(3, 2, 6, base);
+
+        let mut last_value = 0.0;
+        for i in 2..8 {
+            let (r, g, b) = palette.get_color(3, i).to_rgb_f32();
+            let (hue, _, value) = rgb_to_hsv(r, g, b);
+            assert!(
+                (hue - base_hue).abs() < 4.0,
+                "slot {} hue {} vs {}",
+                i,
+                hue,
+                base_hue
+            );
+            assert!(value > last_value, "ramp should brighten");
+            last_value = value;
+        }
+        assert_eq!(palette.get_color(3, 1), Color::default());
+        assert_eq!(palette.get_color(3, 8), Color::default());
+    }
+
+    #[test]
+    fn test_palette_fill_shade_ramp_clips_at_end() {
+        let mut palette = Palette::new();
+        palette.fill_shade_ramp(0, 14, 5, Color::new(31, 0, 0));
+        assert_ne!(palette.get_color(0, 14), Color::default());
+        assert_ne!(palette.get_color(0, 15), Color::default());
+        assert_eq!(palette.get_color(1, 0), Color::default());
+    }
+
+    #[test]
+    fn test_palette_quantize_sprite_few_colors() {
+        let mut palette = Palette::new();
+        palette.set_color(2, 0, Color::new(5, 5, 5));
+        let colors = [(0, 0, 0), (255, 0, 0), (0, 0, 0), (0, 255, 0)];
+
+        let indices = palette.quantize_sprite(2, &colors);
+        assert_eq!(indices[0], indices[2]);
+        assert!(indices.iter().all(|&i| (1..=15).contains(&i)));
+        assert_eq!(palette.get_color(2, indices[1]).rgb(), (31, 0, 0));
+        assert_eq!(palette.get_color(2, indices[3]).rgb(), (0, 31, 0));
+        // Black is opaque here and must not land on the transparent slot
+        assert_eq!(palette.get_color(2, 0).rgb(), (5, 5, 5));
+    }
+
+    #[test]
+    fn test_palette_quantize_sprite_never_uses_index_zero() {
+        let colors: Vec<(u8, u8, u8)> = (0..64u32)
+            .map(|i| ((i * 4) as u8, (255 - i * 4) as u8, ((i * 37) % 256) as u8))
+            .collect();
+
+        let mut palette = Palette::new();
+        let indices = palette.quantize_sprite(0, &colors);
+        assert_eq!(indices.len(), colors.len());
+        assert!(indices.iter().all(|&i| (1..=15).contains(&i)));
+        assert_eq!(palette.get_color(0, 0), Color::default());
+
+        // Assignments agree with the crate-wide weighted distance
+        for (&(r, g, b), &index) in colors.iter().zip(&indices) {
+            let color = Color::from_rgb888_rounded(r, g, b);
+            let best = (1..16)
+                .map(|i| palette.get_color(0, i).distance_squared(&color))
+                .min()
+                .unwrap();
+            assert_eq!(palette.get_color(0, index).distance_squared(&color), best);
+        }
+    }
+
+    #[test]
+    fn test_palette_import_binary_masked() {
+        let mut reference = Palette::new();
+        for i in 0..16 {
+            reference.set_color(0, i, Color::new(i, 0, 31));
+            reference.set_color(1, i, Color::new(31, i, 0));
+        }
+        let data = reference.export_binary();
+
+        let mut palette = Palette::new();
+        palette.set_color(0, 3, Color::new(1, 1, 1));
+        palette.set_color(1, 3, Color::new(2, 2, 2));
+        let mut mask = [false; 256];
+        mask[..16].fill(true);
+        palette.import_binary_masked(&data, &mask).unwrap();
+
+        for i in 0..16 {
+            assert_eq!(palette.get_color(0, i), reference.get_color(0, i));
+        }
+        assert_eq!(palette.get_color(1, 3), Color::new(2, 2, 2));
+        assert_eq!(palette.get_color(1, 4), Color::default());
+
+        let before = palette.clone();
+        assert_eq!(
+            palette.import_binary_masked(&data[..100], &mask),
+            Err(SemitileError::InvalidLength {
+                expected: 512,
+                actual: 100
+            })
+        );
+        assert_eq!(palette, before);
+    }
+
+    #[test]
+    fn test_color_rgb565() {
+        // Pure green expands to the full 6-bit range
+        assert_eq!(Color::new(0, 31, 0).to_rgb565(), 0x07E0);
+        assert_eq!(Color::new(31, 0, 0).to_rgb565(), 0xF800);
+        assert_eq!(Color::new(0, 0, 31).to_rgb565(), 0x001F);
+        assert_eq!(Color::new(0, 16, 0).to_rgb565(), 33 << 5);
+
+        for (r, g, b) in [(0, 0, 0), (31, 31, 31), (3, 17, 29), (12, 1, 8)] {
+            let color = Color::new(r, g, b);
+            assert_eq!(Color::from_rgb565(color.to_rgb565()), color);
+        }
+    }
+
+    #[test]
+    fn test_palette_build_blend_table() {
+        let mut palette = Palette::new();
+        for i in 0..16 {
+            palette.set_color(2, i, Color::new(i * 2, 31 - i, i));
+        }
+
+        let same = palette.build_blend_table(2, 2);
+        for (i, row) in same.iter().enumerate() {
+            assert_eq!(row[i], i as u8);
+        }
+
+        let mut palette = Palette::new();
+        palette.set_color(0, 0, Color::new(0, 0, 0));
+        palette.set_color(0, 1, Color::new(31, 0, 0));
+        palette.set_color(0, 2, Color::new(16, 0, 0));
+        palette.set_color(1, 3, Color::new(31, 0, 0));
+        let cross = palette.build_blend_table(0, 1);
+        assert_eq!(cross[0][3], 2);
+        assert_eq!(cross[1][3], 1);
+
+        // White and near-black mix to (16, 16, 16); the green-heavy weighting
+        // of `distance_squared()` prefers the red offset over the green one
+        let mut palette = Palette::new();
+        for i in 0..16 {
+            palette.set_color(0, i, Color::new(31, 31, 31));
+        }
+        palette.set_color(0, 1, Color::new(16, 19, 16));
+        palette.set_color(0, 2, Color::new(20, 16, 16));
+        palette.set_color(1, 0, Color::new(1, 1, 1));
+        assert_eq!(palette.build_blend_table(0, 1)[0][0], 2);
+    }
+
+    #[test]
+    fn test_color_luminance() {
+        let red = Color::new(31, 0, 0).luminance();
+        let green = Color::new(0, 31, 0).luminance();
+        let blue = Color::new(0, 0, 31).luminance();
+        assert!((red - 0.299).abs() < 1e-6);
+        assert!((green - 0.587).abs() < 1e-6);
+        assert!((blue - 0.114).abs() < 1e-6);
+        assert!(green > red && red > blue);
+        assert!((Color::new(31, 31, 31).luminance() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_color_to_grayscale() {
+        assert_eq!(Color::new(0, 0, 0).to_grayscale(), Color::new(0, 0, 0));
+        assert_eq!(
+            Color::new(31, 31, 31).to_grayscale(),
+            Color::new(31, 31, 31)
+        );
+        // 0.587 * 31 = 18.197
+        assert_eq!(Color::new(0, 31, 0).to_grayscale(), Color::new(18, 18, 18));
+    }
+
+    #[test]
+    fn test_color_blend() {
+        let black = Color::new(0, 0, 0);
+        let white = Color::new(31, 31, 31);
+        let (r, g, b) = black.blend(&white, 0.5).rgb();
+        assert!(r == 15 || r == 16);
+        assert_eq!((r, g, b), (r, r, r));
+
+        let color = Color::new(4, 20, 9);
+        assert_eq!(color.blend(&white, 0.0), color);
+        assert_eq!(color.blend(&white, 1.0), white);
+        assert_eq!(color.blend(&white, -2.0), color);
+        assert_eq!(color.blend(&white, 7.5), white);
+    }
+
+    #[test]
+    fn test_color_gradient() {
+        let black = Color::new(0, 0, 0);
+        let white = Color::new(31, 31, 31);
+        let levels: Vec<u8> = Color::gradient(black, white, 5)
+            .iter()
+            .map(|color| color.rgb().0)
+            .collect();
+        assert_eq!(levels, vec![0, 8, 16, 23, 31]);
+
+        assert_eq!(Color::gradient(black, white, 1), vec![black]);
+        assert!(Color::gradient(black, white, 0).is_empty());
+        assert_eq!(Color::lerp(black, white, 1.0), white);
+    }
+
+    #[test]
+    fn test_color_distance_squared() {
+        let colors = [
+            Color::new(0, 0, 0),
+            Color::new(31, 31, 31),
+            Color::new(12, 3, 27),
+            Color::new(5, 30, 1),
+        ];
+        for a in &colors {
+            assert_eq!(a.distance_squared(a), 0);
+            for b in &colors {
+                assert_eq!(a.distance_squared(b), b.distance_squared(a));
+            }
+        }
+        assert_eq!(colors[0].distance_squared(&colors[1]), 9 * 31 * 31);
+        // Green differences weigh more than red ones
+        let base = Color::new(10, 10, 10);
+        assert!(
+            base.distance_squared(&Color::new(10, 12, 10))
+                > base.distance_squared(&Color::new(12, 10, 10))
+        );
+    }
+
+    #[test]
+    fn test_color_hex_round_trip() {
+        let red = Color::from_hex("#FF0000").unwrap();
+        assert_eq!(red, Color::new(31, 0, 0));
+        assert_eq!(red.to_hex(), "#FF0000");
+
+        assert_eq!(Color::from_hex("ff0000"), Some(red));
+        assert_eq!(Color::from_hex("#f00"), Some(red));
+        assert_eq!(
+            Color::from_hex("#0F8"),
+            Some(Color::from_rgb888(0, 0xFF, 0x88))
+        );
+        assert_eq!(Color::new(0, 16, 31).to_hex(), "#0084FF");
+    }
+
+    #[test]
+    fn test_color_from_hex_rejects_malformed() {
+        for input in [
+            "", "#", "#12345", "#1234567", "#GG0000", "##FF0000", "+FF00", "#ÿ0",
+        ] {
+            assert_eq!(Color::from_hex(input), None, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_color_lighten_darken() {
+        let white = Color::new(31, 31, 31);
+        let black = Color::new(0, 0, 0);
+        assert_eq!(white.lighten(5), white);
+        assert_eq!(black.darken(5), black);
+        assert_eq!(Color::new(10, 29, 2).lighten(4), Color::new(14, 31, 6));
+        assert_eq!(Color::new(10, 29, 2).darken(4), Color::new(6, 25, 0));
+        assert_eq!(black.lighten(255), white);
+    }
+
+    #[test]
+    fn test_color_scale() {
+        assert_eq!(Color::new(30, 9, 1).scale(0.5), Color::new(15, 5, 1));
+        assert_eq!(Color::new(30, 9, 1).scale(0.5), Color::new(30, 9, 1) * 0.5);
+        assert_eq!(Color::new(20, 20, 20).scale(2.0), Color::new(31, 31, 31));
+        assert_eq!(Color::new(20, 20, 20).scale(-1.0), Color::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_color_from_rgb888_rounded() {
+        for level in 0..32 {
+            let (r, g, b) = Color::new(level, level, level).to_rgb888();
+            assert_eq!(
+                Color::from_rgb888_rounded(r, g, b),
+                Color::new(level, level, level)
+            );
+        }
+        // Mid-gray 132 is exactly level 16 expanded, so both conversions agree
+        assert_eq!(Color::new(16, 16, 16).to_rgb888(), (132, 132, 132));
+        assert_eq!(Color::from_rgb888(132, 132, 132).rgb(), (16, 16, 16));
+        assert_eq!(
+            Color::from_rgb888_rounded(132, 132, 132).rgb(),
+            (16, 16, 16)
+        );
+
+        // 15 is nearer to 2 (expands to 16) than to 1 (expands to 8)
+        assert_eq!(Color::from_rgb888(15, 15, 15).rgb(), (1, 1, 1));
+        assert_eq!(Color::from_rgb888_rounded(15, 15, 15).rgb(), (2, 2, 2));
+
+        assert_eq!(Color::from_rgb888_rounded(255, 0, 3).rgb(), (31, 0, 0));
+        assert_eq!(Color::from_rgb888_rounded(252, 4, 11).rgb(), (31, 0, 1));
+    }
+
+    #[test]
+    fn test_palette_find_nearest() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 0, Color::new(31, 31, 31));
+        palette.set_color(4, 9, Color::new(20, 5, 12));
+        palette.set_color(7, 2, Color::new(3, 28, 3));
+
+        assert_eq!(palette.find_nearest(Color::new(20, 5, 12)), (4, 9));
+        assert_eq!(palette.find_nearest(Color::new(4, 27, 3)), (7, 2));
+        // Black fills every other slot; the first one wins the tie
+        assert_eq!(palette.find_nearest(Color::new(0, 0, 0)), (0, 1));
+
+        assert_eq!(palette.find_nearest_in_sub(4, Color::new(19, 6, 12)), 9);
+        assert_eq!(palette.find_nearest_in_sub(20, Color::new(19, 6, 12)), 9);
+        assert_eq!(palette.find_nearest_in_sub(7, Color::new(19, 6, 12)), 0);
+    }
+
+    #[test]
+    fn test_palette_quantize_indices() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 1, Color::new(31, 0, 0));
+        palette.set_color(0, 2, Color::new(0, 31, 0));
+        palette.set_color(2, 3, Color::new(0, 0, 31));
+        palette.set_color(2, 4, Color::new(31, 31, 31));
+
+        let rgba = [
+            255, 0, 0, 255, 0, 255, 0, 255, // Red, green
+            0, 0, 255, 255, 250, 250, 250, 0, // Blue, transparent white
+        ];
+        assert_eq!(palette.quantize_indices(&rgba, 2, 2), vec![1, 2, 35, 36]);
+        assert!(palette.quantize_indices(&rgba, 2, 1).is_empty());
+
+        // Overflowing dimensions are rejected instead of wrapping to a match
+        assert!(palette.quantize_indices(&rgba, usize::MAX, 2).is_empty());
+        let wraps_to_16 = (1usize << (usize::BITS - 2)) + 4;
+        assert!(palette.quantize_indices(&rgba, wraps_to_16, 1).is_empty());
+    }
+
+    #[test]
+    fn test_palette_from_jasc_pal() {
+        let text = "JASC-PAL\r\n0100\r\n4\r\n0 0 0\r\n255 0 0\r\n132 132 132\r\n8 16 250 255\r\n";
+        let palette = Palette::from_jasc_pal(text).unwrap();
+        assert_eq!(palette.get_color(0, 1), Color::new(31, 0, 0));
+        assert_eq!(palette.get_color(0, 2), Color::new(16, 16, 16));
+        assert_eq!(palette.get_color(0, 3), Color::new(1, 2, 30));
+        assert_eq!(palette.get_color(0, 4), Color::new(0, 0, 0));
+        assert_eq!(palette.get_color(15, 15), Color::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_palette_from_jasc_pal_malformed() {
+        assert_eq!(
+            Palette::from_jasc_pal("GIMP Palette\n0100\n1\n0 0 0\n"),
+            None
+        );
+        assert_eq!(Palette::from_jasc_pal("JASC-PAL\n0200\n1\n0 0 0\n"), None);
+        assert_eq!(Palette::from_jasc_pal("JASC-PAL\n0100\nmany\n"), None);
+        assert_eq!(Palette::from_jasc_pal("JASC-PAL\n0100\n2\n0 0 0\n"), None);
+        assert_eq!(Palette::from_jasc_pal("JASC-PAL\n0100\n1\n0 0 256\n"), None);
+        assert_eq!(Palette::from_jasc_pal("JASC-PAL\n0100\n1\n0 0\n"), None);
+    }
+
+    #[test]
+    fn test_palette_from_gimp_gpl() {
+        let mut text = String::from("GIMP Palette\nName: Test\nColumns: 16\n#\n# A comment\n");
+        for i in 0..17u8 {
+            text.push_str(&format!(
+                "{:3} {:3} {:3}\tShade {}\n",
+                i * 8,
+                0,
+                255 - i * 8,
+                i
+            ));
+        }
+        text.push_str("\n255 255 255\n");
+
+        let palette = Palette::from_gimp_gpl(&text).unwrap();
+        assert_eq!(palette.get_color(0, 0), Color::new(0, 0, 31));
+        assert_eq!(palette.get_color(0, 15), Color::new(15, 0, 16));
+        assert_eq!(palette.get_color(1, 0), Color::new(16, 0, 15));
+        assert_eq!(palette.get_color(1, 1), Color::new(31, 31, 31));
+        assert_eq!(palette.get_color(1, 2), Color::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_palette_from_gimp_gpl_malformed() {
+        assert_eq!(Palette::from_gimp_gpl("JASC-PAL\n0 0 0\n"), None);
+        assert_eq!(Palette::from_gimp_gpl(""), None);
+        assert_eq!(Palette::from_gimp_gpl("\nGIMP Palette\n0 0 x Name\n"), None);
+        assert!(Palette::from_gimp_gpl("\n\nGIMP Palette\n").is_some());
+    }
+
+    #[test]
+    fn test_palette_jasc_pal_round_trip() {
+        let mut palette = Palette::new();
+        for i in 0..=255u8 {
+            palette.set_color(i / 16, i % 16, Color::from_rgb555(i as u16 * 127));
+        }
+
+        let text = palette.to_jasc_pal();
+        assert!(text.starts_with("JASC-PAL\n0100\n256\n"));
+        assert_eq!(text.lines().count(), 3 + 256);
+
+        assert_eq!(Palette::from_jasc_pal(&text), Some(palette));
+    }
+
+    #[test]
+    fn test_palette_to_swatch_rgba() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 0, Color::new(31, 0, 0));
+        palette.set_color(3, 5, Color::new(0, 31, 16));
+
+        let (rgba, width, height) = palette.to_swatch_rgba(1);
+        assert_eq!((width, height), (16, 16));
+        assert_eq!(rgba.len(), 16 * 16 * 4);
+        assert_eq!(rgba[..4], [255, 0, 0, 255]);
+        let offset = (3 * 16 + 5) * 4;
+        assert_eq!(rgba[offset..offset + 4], [0, 255, 132, 255]);
+
+        let (rgba, width, height) = palette.to_swatch_rgba(3);
+        assert_eq!((width, height), (48, 48));
+        let offset = ((3 * 3 + 2) * 48 + 5 * 3 + 2) * 4;
+        assert_eq!(rgba[offset..offset + 4], [0, 255, 132, 255]);
+    }
+
+    #[test]
+    fn test_palette_sort_sub_palette() {
+        use crate::Tile;
+
+        let mut palette = Palette::new();
+        for i in 0..16 {
+            palette.set_color(6, i, Color::new((i * 7) % 32, (i * 13) % 32, i));
+        }
+        let original = palette.clone();
+
+        let remap = palette.sort_sub_palette(6);
+        for i in 0..16u8 {
+            assert_eq!(
+                palette.get_color(6, remap[i as usize]),
+                original.get_color(6, i)
+            );
+        }
+        for i in 1..16 {
+            assert!(palette.get_color(6, i - 1).luminance() <= palette.get_color(6, i).luminance());
+        }
+
+        let mut tile = Tile::from_flat(&std::array::from_fn(|i| (i % 16) as u8));
+        let colors_before: Vec<Color> = tile
+            .pixels()
+            .map(|(_, _, c)| original.get_color(6, c))
+            .collect();
+        tile.remap_colors(&remap);
+        let colors_after: Vec<Color> = tile
+            .pixels()
+            .map(|(_, _, c)| palette.get_color(6, c))
+            .collect();
+        assert_eq!(colors_before, colors_after);
+    }
+
+    #[test]
+    fn test_palette_sort_sub_palette_keeping_first() {
+        let mut palette = Palette::new();
+        palette.set_color(2, 0, Color::new(31, 31, 31));
+        palette.set_color(2, 1, Color::new(20, 20, 20));
+        palette.set_color(2, 2, Color::new(10, 10, 10));
+        for i in 3..16 {
+            palette.set_color(2, i, Color::new(31, 31, 31));
+        }
+        let original = palette.clone();
+
+        let remap = palette.sort_sub_palette_keeping_first(2);
+        assert_eq!(remap[0], 0);
+        assert_eq!(palette.get_color(2, 0), Color::new(31, 31, 31));
+        assert_eq!(palette.get_color(2, 1), Color::new(10, 10, 10));
+        assert_eq!(palette.get_color(2, 2), Color::new(20, 20, 20));
+        for i in 0..16u8 {
+            assert_eq!(
+                palette.get_color(2, remap[i as usize]),
+                original.get_color(2, i)
+            );
+        }
+    }
+
+    #[test]
+    fn test_palette_swap_sub_palettes() {
+        let mut palette = Palette::new();
+        for i in 0..16 {
+            palette.set_color(1, i, Color::new(i, 0, 0));
+            palette.set_color(9, i, Color::new(0, i, 31));
+        }
+        let original = palette.clone();
+
+        palette.swap_sub_palettes(1, 9);
+        assert_eq!(palette.get_color(1, 5), Color::new(0, 5, 31));
+        assert_eq!(palette.get_color(9, 5), Color::new(5, 0, 0));
+        palette.swap_sub_palettes(17, 25);
+        assert_eq!(palette, original);
+    }
+
+    #[test]
+    fn test_palette_copy_sub_palette() {
+        let mut palette = Palette::new();
+        for i in 0..16 {
+            palette.set_color(3, i, Color::new(i, i, 0));
+        }
+        palette.set_color(12, 0, Color::new(31, 31, 31));
+        let original = palette.clone();
+
+        palette.copy_sub_palette(3, 28);
+        for i in 0..16 {
+            assert_eq!(palette.get_color(12, i), Color::new(i, i, 0));
+            assert_eq!(palette.get_color(3, i), original.get_color(3, i));
+        }
+    }
+
+    #[test]
+    fn test_palette_unique_color_count() {
+        let mut palette = Palette::new();
+        assert_eq!(palette.unique_color_count(), 1);
+
+        palette.set_color(0, 1, Color::new(31, 0, 0));
+        palette.set_color(4, 2, Color::new(31, 0, 0));
+        palette.set_color(7, 7, Color::new(0, 31, 0));
+        palette.set_color(15, 15, Color::new(1, 2, 3));
+        assert_eq!(palette.unique_color_count(), 4);
+    }
+
+    #[test]
+    fn test_palette_get_set_sub_palette() {
+        let mut palette = Palette::new();
+        let ramp = Color::gradient(Color::new(0, 0, 8), Color::new(31, 31, 31), 16);
+        let ramp: [Color; 16] = ramp.try_into().unwrap();
+
+        palette.set_sub_palette(21, &ramp);
+        assert_eq!(palette.get_sub_palette(5), ramp);
+        assert_eq!(palette.get_sub_palette(21), ramp);
+        assert_eq!(palette.get_color(5, 15), Color::new(31, 31, 31));
+        for sub in (0..16).filter(|&sub| sub != 5) {
+            assert_eq!(palette.get_sub_palette(sub), [Color::default(); 16]);
+        }
+    }
+
+    #[test]
+    fn test_palette_from_gimp_gpl_keeps_expanded_colors() {
+        let mut text = String::from("GIMP Palette\n");
+        for level in 0..32 {
+            let (r, g, b) = Color::new(level, 31 - level, level / 2).to_rgb888();
+            text.push_str(&format!("{} {} {}\n", r, g, b));
+        }
+        let palette = Palette::from_gimp_gpl(&text).unwrap();
+        for level in 0..32u8 {
+            assert_eq!(
+                palette.get_color(level / 16, level % 16),
+                Color::new(level, 31 - level, level / 2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_preview_quantized_rgba_matches_quantize_indices() {
+        let mut palette = Palette::new();
+        for i in 0..16 {
+            palette.set_color(1, i, Color::new(i * 2, 31 - i * 2, i));
+        }
+        let pixels: Vec<u8> = (0..64u8)
+            .flat_map(|i| {
+                [
+                    i.wrapping_mul(37),
+                    i.wrapping_mul(91),
+                    i.wrapping_mul(13),
+                    255,
+                ]
+            })
+            .collect();
+
+        let indices = palette.quantize_indices(&pixels, 8, 8);
+        let preview = preview_quantized_rgba(&pixels, 8, 8, &palette);
+        for (px, &index) in preview.chunks_exact(4).zip(&indices) {
+            let (r, g, b) = palette.get_color(index / 16, index % 16).to_rgb888();
+            assert_eq!(px, [r, g, b, 255]);
+        }
+
+        // 15 rounds up to level 2 instead of truncating to level 1
+        palette.set_color(0, 0, Color::new(1, 1, 1));
+        palette.set_color(0, 1, Color::new(2, 2, 2));
+        assert_eq!(palette.quantize_indices(&[15, 15, 15, 255], 1, 1), vec![1]);
+    }
 }
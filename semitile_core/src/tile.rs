@@ -15,8 +15,52 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::hash::{Hash, Hasher};
+
+use crate::error::SemitileError;
+use crate::palette::{Color, Palette};
+use crate::rng::SplitMix64;
+
+/// RGBA color used to highlight differing pixels in `render_diff_rgba()`
+const DIFF_COLOR: [u8; 4] = [255, 0, 255, 255];
+
+/// Checkerboard colors (light, dark) used behind transparent pixels
+const CHECKER_COLORS: [[u8; 4]; 2] = [[204, 204, 204, 255], [153, 153, 153, 255]];
+
+/// Size in pixels of one checkerboard square
+const CHECKER_CELL: usize = 2;
+
+/// Options for `Tile::render_rgba_with()`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Color drawn instead of any palette slot still holding the default
+    /// placeholder (black), so undefined colors stand out (e.g. bright pink)
+    pub fallback_color: Option<Color>,
+}
+
+/// Clockwise rotation applied by `Tile::transform()`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    /// No rotation
+    #[default]
+    Cw0,
+    /// 90° clockwise
+    Cw90,
+    /// 180°
+    Cw180,
+    /// 270° clockwise (90° counter-clockwise)
+    Cw270,
+}
+
 /// Represents an 8×8 tile with 4-bit color indices (0-15)
+///
+/// With the `serde` feature, serializes as 8 rows of 8 color indices
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[[u8; 8]; 8]", into = "[[u8; 8]; 8]")
+)]
 pub struct Tile {
     pixels: [[u8; 8]; 8],
 }
@@ -29,6 +73,22 @@ impl Tile {
         }
     }
 
+    /// Creates a tile filled with deterministic pseudo-random color indices
+    ///
+    /// The same seed always produces the same tile, making this suitable for
+    /// reproducible tests and benchmarks
+    pub fn pseudo_random(seed: u64) -> Self {
+        let mut rng = SplitMix64::new(seed);
+        let mut tile = Tile::new();
+        for row in &mut tile.pixels {
+            let bits = rng.next_u64();
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = ((bits >> (x * 4)) & 0xF) as u8;
+            }
+        }
+        tile
+    }
+
     /// Sets a pixel at the given coordinates to the specified color index (0-15)
     ///
     /// # Arguments
@@ -54,247 +114,2054 @@ impl Tile {
         if x < 8 && y < 8 { self.pixels[y][x] } else { 0 }
     }
 
-    /// Converts the tile to 4bpp planar format (32 bytes)
-    ///
-    /// The planar format organizes data into four 8-byte bit planes:
-    /// - Bytes 0-7: Bit Plane 0 (LSB of color index)
-    /// - Bytes 8-15: Bit Plane 1
-    /// - Bytes 16-23: Bit Plane 2
-    /// - Bytes 24-31: Bit Plane 3 (MSB of color index)
+    /// Gets the 8 color indices of a row, left to right
     ///
-    /// Within each plane, each byte represents one row of 8 pixels,
-    /// with bit 7 being the leftmost pixel (MSB first)
-    pub fn to_planar(&self) -> [u8; 32] {
-        let mut planar = [0u8; 32];
-
-        for y in 0..8 {
-            for x in 0..8 {
-                let color = self.pixels[y][x];
-                let bit_pos = 7 - x; // MSB first (bit 7 = leftmost pixel)
+    /// Returns None if `y` is out of bounds
+    pub fn row(&self, y: usize) -> Option<[u8; 8]> {
+        self.pixels.get(y).copied()
+    }
 
-                // Distribute each bit of the 4-bit color across the four planes
-                if color & 0b0001 != 0 {
-                    planar[y] |= 1 << bit_pos; // Plane 0
-                }
-                if color & 0b0010 != 0 {
-                    planar[8 + y] |= 1 << bit_pos; // Plane 1
-                }
-                if color & 0b0100 != 0 {
-                    planar[16 + y] |= 1 << bit_pos; // Plane 2
-                }
-                if color & 0b1000 != 0 {
-                    planar[24 + y] |= 1 << bit_pos; // Plane 3
-                }
+    /// Overwrites a row with the given color indices, left to right
+    ///
+    /// Values above 15 are clamped to 15. If `y` is out of bounds, the
+    /// operation is ignored
+    pub fn set_row(&mut self, y: usize, row: &[u8; 8]) {
+        if let Some(pixels) = self.pixels.get_mut(y) {
+            for (pixel, &color) in pixels.iter_mut().zip(row) {
+                *pixel = color.min(15);
             }
         }
-
-        planar
     }
 
-    /// Creates a tile from 4bpp planar format data (32 bytes)
+    /// Gets the 8 color indices of a column, top to bottom
     ///
-    /// See `to_planar()` for format description
-    pub fn from_planar(data: &[u8; 32]) -> Self {
-        let mut tile = Tile::new();
-
-        for y in 0..8 {
-            for x in 0..8 {
-                let bit_pos = 7 - x;
-                let mut color = 0u8;
-
-                // Reconstruct 4-bit color from the four planes
-                if data[y] & (1 << bit_pos) != 0 {
-                    color |= 0b0001;
-                }
-                if data[8 + y] & (1 << bit_pos) != 0 {
-                    color |= 0b0010;
-                }
-                if data[16 + y] & (1 << bit_pos) != 0 {
-                    color |= 0b0100;
-                }
-                if data[24 + y] & (1 << bit_pos) != 0 {
-                    color |= 0b1000;
-                }
+    /// Returns None if `x` is out of bounds
+    pub fn column(&self, x: usize) -> Option<[u8; 8]> {
+        if x < 8 {
+            Some(std::array::from_fn(|y| self.pixels[y][x]))
+        } else {
+            None
+        }
+    }
 
-                tile.pixels[y][x] = color;
+    /// Overwrites a column with the given color indices, top to bottom
+    ///
+    /// Values above 15 are clamped to 15. If `x` is out of bounds, the
+    /// operation is ignored
+    pub fn set_column(&mut self, x: usize, column: &[u8; 8]) {
+        if x < 8 {
+            for (pixels, &color) in self.pixels.iter_mut().zip(column) {
+                pixels[x] = color.min(15);
             }
         }
-
-        tile
     }
-}
 
-impl Default for Tile {
-    fn default() -> Self {
-        Self::new()
+    /// Iterates over all pixels as `(x, y, color)` in row-major order
+    pub fn pixels(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        self.pixels
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, &color)| (x, y, color)))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Paints a square brush centered on the given pixel
+    ///
+    /// # Arguments
+    /// * `cx` - Brush center X coordinate
+    /// * `cy` - Brush center Y coordinate
+    /// * `radius` - Chebyshev radius (0 paints a single pixel, 1 a 3×3 block)
+    /// * `color` - Color index (0-15)
+    ///
+    /// The brush is clipped to the tile bounds. If color > 15, the operation is ignored
+    pub fn paint(&mut self, cx: usize, cy: usize, radius: usize, color: u8) {
+        if color > 15 {
+            return;
+        }
+        for y in cy.saturating_sub(radius)..=cy.saturating_add(radius).min(7) {
+            for x in cx.saturating_sub(radius)..=cx.saturating_add(radius).min(7) {
+                self.pixels[y][x] = color;
+            }
+        }
+    }
 
-    #[test]
-    fn test_new_tile_is_empty() {
-        let tile = Tile::new();
-        for y in 0..8 {
-            for x in 0..8 {
-                assert_eq!(tile.get_pixel(x, y), 0);
+    /// Fills a rectangle with a color
+    ///
+    /// # Arguments
+    /// * `x`, `y` - Top-left corner
+    /// * `w`, `h` - Size in pixels (0 draws nothing)
+    /// * `color` - Color index (0-15)
+    ///
+    /// The rectangle is clipped to the tile bounds. If color > 15, the operation is ignored
+    pub fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, color: u8) {
+        if color > 15 {
+            return;
+        }
+        for row in self.pixels.iter_mut().take(y.saturating_add(h)).skip(y) {
+            for pixel in row.iter_mut().take(x.saturating_add(w)).skip(x) {
+                *pixel = color;
             }
         }
     }
 
-    #[test]
-    fn test_set_and_get_pixel() {
-        let mut tile = Tile::new();
-        tile.set_pixel(3, 4, 7);
-        assert_eq!(tile.get_pixel(3, 4), 7);
-        assert_eq!(tile.get_pixel(0, 0), 0);
+    /// Copies a rectangle of pixels from another tile
+    ///
+    /// # Arguments
+    /// * `src` - Tile to copy from
+    /// * `src_x`, `src_y` - Top-left corner of the region in `src`
+    /// * `w`, `h` - Size in pixels (0 copies nothing)
+    /// * `dst_x`, `dst_y` - Top-left corner of the destination in `self`
+    ///
+    /// Color indices are copied as-is, including 0. Any part of the region
+    /// falling outside either tile is clipped
+    #[allow(clippy::too_many_arguments)]
+    pub fn blit(
+        &mut self,
+        src: &Tile,
+        src_x: usize,
+        src_y: usize,
+        w: usize,
+        h: usize,
+        dst_x: usize,
+        dst_y: usize,
+    ) {
+        let w = w
+            .min(8usize.saturating_sub(src_x))
+            .min(8usize.saturating_sub(dst_x));
+        let h = h
+            .min(8usize.saturating_sub(src_y))
+            .min(8usize.saturating_sub(dst_y));
+        for dy in 0..h {
+            for dx in 0..w {
+                self.pixels[dst_y + dy][dst_x + dx] = src.pixels[src_y + dy][src_x + dx];
+            }
+        }
     }
 
-    #[test]
-    fn test_set_pixel_bounds_checking() {
-        let mut tile = Tile::new();
+    /// Draws the one-pixel border of a rectangle
+    ///
+    /// Same arguments and clipping as `fill_rect()`, but only the outermost
+    /// rows and columns of the rectangle are painted
+    pub fn draw_rect(&mut self, x: usize, y: usize, w: usize, h: usize, color: u8) {
+        if w == 0 || h == 0 {
+            return;
+        }
+        let right = x.saturating_add(w - 1);
+        let bottom = y.saturating_add(h - 1);
+        self.fill_rect(x, y, w, 1, color);
+        self.fill_rect(x, bottom, w, 1, color);
+        self.fill_rect(x, y, 1, h, color);
+        self.fill_rect(right, y, 1, h, color);
+    }
 
-        // Out of bounds - should be ignored
-        tile.set_pixel(8, 0, 5);
-        tile.set_pixel(0, 8, 5);
-        tile.set_pixel(10, 10, 5);
+    /// Draws a straight line between two points using Bresenham's algorithm
+    ///
+    /// # Arguments
+    /// * `x0`, `y0` - Start point
+    /// * `x1`, `y1` - End point
+    /// * `color` - Color index (0-15)
+    ///
+    /// Endpoints may lie anywhere in the `i32` range. The segment is first
+    /// clipped to the tile (Liang–Barsky), so only the in-bounds portion is
+    /// walked and far-away endpoints cost nothing extra. If color > 15, the
+    /// operation is ignored
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u8) {
+        if color > 15 {
+            return;
+        }
+        let Some((x0, y0, x1, y1)) = clip_line(x0, y0, x1, y1) else {
+            return;
+        };
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        let (mut x, mut y) = (x0, y0);
+        loop {
+            self.pixels[y as usize][x as usize] = color;
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
 
-        // Color too large - should be ignored
-        tile.set_pixel(0, 0, 16);
-        assert_eq!(tile.get_pixel(0, 0), 0);
+    /// Recolors every pixel using one color index to another
+    ///
+    /// Unlike `flood_fill()`, this applies to the whole tile regardless of
+    /// connectivity. Does nothing if `from == to` or either index is above 15.
+    ///
+    /// Returns the number of pixels changed
+    pub fn replace_color(&mut self, from: u8, to: u8) -> usize {
+        if from == to || from > 15 || to > 15 {
+            return 0;
+        }
+        let mut changed = 0;
+        for pixel in self.pixels.iter_mut().flatten() {
+            if *pixel == from {
+                *pixel = to;
+                changed += 1;
+            }
+        }
+        changed
     }
 
-    #[test]
-    fn test_get_pixel_out_of_bounds() {
-        let tile = Tile::new();
-        assert_eq!(tile.get_pixel(8, 0), 0);
-        assert_eq!(tile.get_pixel(0, 8), 0);
-        assert_eq!(tile.get_pixel(100, 100), 0);
+    /// Swaps two color indices everywhere they are used in the tile
+    ///
+    /// Pixels of color `a` become `b` and vice versa in a single pass. Does
+    /// nothing if `a == b` or either index is above 15
+    pub fn swap_colors(&mut self, a: u8, b: u8) {
+        if a == b || a > 15 || b > 15 {
+            return;
+        }
+        for pixel in self.pixels.iter_mut().flatten() {
+            if *pixel == a {
+                *pixel = b;
+            } else if *pixel == b {
+                *pixel = a;
+            }
+        }
     }
 
-    #[test]
-    fn test_planar_conversion_empty_tile() {
-        let tile = Tile::new();
-        let planar = tile.to_planar();
+    /// Remaps every pixel's color index through a lookup table
+    ///
+    /// Each pixel of color `c` becomes `lut[c]`; LUT entries above 15 are
+    /// clamped to 15. Generalizes `swap_colors()` and `replace_color()` to
+    /// arbitrary permutations and merges.
+    pub fn remap_colors(&mut self, lut: &[u8; 16]) {
+        for pixel in self.pixels.iter_mut().flatten() {
+            *pixel = lut[*pixel as usize].min(15);
+        }
+    }
 
-        // All bytes should be 0 for an empty tile
-        for byte in planar.iter() {
-            assert_eq!(*byte, 0);
+    /// Replaces the 4-connected region of one color starting at a pixel
+    ///
+    /// # Arguments
+    /// * `x` - Start X coordinate (0-7)
+    /// * `y` - Start Y coordinate (0-7)
+    /// * `new_color` - Replacement color index (0-15)
+    ///
+    /// Every pixel orthogonally connected to `(x, y)` with the same color as
+    /// it is recolored; diagonal neighbors are not connected. Does nothing if
+    /// the coordinates are out of bounds, `new_color` > 15, or the start pixel
+    /// already has `new_color`.
+    pub fn flood_fill(&mut self, x: usize, y: usize, new_color: u8) {
+        if x >= 8 || y >= 8 || new_color > 15 {
+            return;
+        }
+        let target = self.pixels[y][x];
+        if target == new_color {
+            return;
         }
 
-        // Round-trip conversion
-        let tile2 = Tile::from_planar(&planar);
-        assert_eq!(tile, tile2);
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            if self.pixels[y][x] != target {
+                continue;
+            }
+            self.pixels[y][x] = new_color;
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if x < 7 {
+                stack.push((x + 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+            if y < 7 {
+                stack.push((x, y + 1));
+            }
+        }
     }
 
-    #[test]
-    fn test_planar_conversion_single_pixel() {
-        let mut tile = Tile::new();
-        tile.set_pixel(0, 0, 0b1111); // Color 15 at top-left
+    /// Flips the tile horizontally in place (mirrors left and right)
+    pub fn flip_h(&mut self) {
+        for row in &mut self.pixels {
+            row.reverse();
+        }
+    }
 
-        let planar = tile.to_planar();
+    /// Flips the tile vertically in place (mirrors top and bottom)
+    pub fn flip_v(&mut self) {
+        self.pixels.reverse();
+    }
 
-        // Top-left pixel (bit 7) should be set in all four planes
-        assert_eq!(planar[0], 0b10000000); // Plane 0, row 0
-        assert_eq!(planar[8], 0b10000000); // Plane 1, row 0
-        assert_eq!(planar[16], 0b10000000); // Plane 2, row 0
-        assert_eq!(planar[24], 0b10000000); // Plane 3, row 0
+    /// Shifts all pixels by the given offset, wrapping around the tile edges
+    ///
+    /// Pixel `(x, y)` moves to `((x + dx) mod 8, (y + dy) mod 8)`. Negative
+    /// offsets shift left/up; offsets that are multiples of 8 are no-ops
+    pub fn shift(&mut self, dx: i32, dy: i32) {
+        self.pixels.rotate_right(dy.rem_euclid(8) as usize);
+        for row in &mut self.pixels {
+            row.rotate_right(dx.rem_euclid(8) as usize);
+        }
+    }
 
-        // Round-trip conversion
-        let tile2 = Tile::from_planar(&planar);
-        assert_eq!(tile, tile2);
+    /// Returns a copy of the tile with the given flips applied
+    ///
+    /// Matches how hardware draws a tilemap entry with the same flip flags
+    pub fn flipped(&self, h_flip: bool, v_flip: bool) -> Tile {
+        let mut tile = self.clone();
+        if h_flip {
+            tile.flip_h();
+        }
+        if v_flip {
+            tile.flip_v();
+        }
+        tile
     }
 
-    #[test]
-    fn test_planar_conversion_single_pixel_color_5() {
-        let mut tile = Tile::new();
-        tile.set_pixel(7, 0, 0b0101); // Color 5 at top-right
+    /// Returns all four flip variants of the tile with their flip flags
+    ///
+    /// Order: original, H-flipped, V-flipped, H+V-flipped. Each element is
+    /// `(tile, h_flip, v_flip)` where `self.flipped(h_flip, v_flip) == tile`.
+    pub fn flip_variants(&self) -> [(Tile, bool, bool); 4] {
+        let mut h = self.clone();
+        h.flip_h();
+        let mut v = self.clone();
+        v.flip_v();
+        let mut hv = h.clone();
+        hv.flip_v();
+        [
+            (self.clone(), false, false),
+            (h, true, false),
+            (v, false, true),
+            (hv, true, true),
+        ]
+    }
 
-        let planar = tile.to_planar();
+    /// Returns the canonical flip variant of the tile with its flip flags
+    ///
+    /// The canonical tile is the lexicographically smallest of the four flip
+    /// variants (comparing color indices in row-major order), so all variants
+    /// of a tile share the same canonical form and can key a `HashMap`.
+    /// `canonical.flipped(h_flip, v_flip)` reproduces `self`, so the flags
+    /// can be stored directly in a `TilemapEntry`.
+    pub fn canonical(&self) -> (Tile, bool, bool) {
+        let [first, rest @ ..] = self.flip_variants();
+        rest.into_iter().fold(first, |best, candidate| {
+            if candidate.0.pixels < best.0.pixels {
+                candidate
+            } else {
+                best
+            }
+        })
+    }
 
-        // Top-right pixel (bit 0) should be set in planes 0 and 2 only
-        // Color 5 = 0b0101 = planes 0 and 2
-        assert_eq!(planar[0], 0b00000001); // Plane 0, row 0
-        assert_eq!(planar[8], 0b00000000); // Plane 1, row 0
-        assert_eq!(planar[16], 0b00000001); // Plane 2, row 0
-        assert_eq!(planar[24], 0b00000000); // Plane 3, row 0
+    /// Transposes the tile in place along its main diagonal
+    ///
+    /// Pixels `(x, y)` and `(y, x)` swap places. Combined with the flips this
+    /// gives all eight symmetries of the tile.
+    pub fn transpose(&mut self) {
+        for y in 0..8 {
+            for x in (y + 1)..8 {
+                let pixel = self.pixels[y][x];
+                self.pixels[y][x] = self.pixels[x][y];
+                self.pixels[x][y] = pixel;
+            }
+        }
+    }
 
-        // Round-trip conversion
-        let tile2 = Tile::from_planar(&planar);
-        assert_eq!(tile, tile2);
+    /// Returns a transposed copy of the tile (see `transpose()`)
+    pub fn transposed(&self) -> Tile {
+        let mut tile = self.clone();
+        tile.transpose();
+        tile
     }
 
-    #[test]
-    fn test_planar_conversion_full_row() {
+    /// Returns a copy of the tile rotated 90° clockwise
+    ///
+    /// Pixel `(x, y)` moves to `(7 - y, x)`
+    pub fn rotate_90_cw(&self) -> Tile {
         let mut tile = Tile::new();
-        // Set first row to alternating colors
-        for x in 0..8 {
-            tile.set_pixel(x, 0, (x % 2) as u8);
+        for y in 0..8 {
+            for x in 0..8 {
+                tile.pixels[x][7 - y] = self.pixels[y][x];
+            }
         }
+        tile
+    }
 
-        let planar = tile.to_planar();
-
-        // First row should be 0b01010101 in plane 0, 0 in others
-        assert_eq!(planar[0], 0b01010101);
-        assert_eq!(planar[8], 0b00000000);
-        assert_eq!(planar[16], 0b00000000);
-        assert_eq!(planar[24], 0b00000000);
-
-        // Round-trip conversion
-        let tile2 = Tile::from_planar(&planar);
-        assert_eq!(tile, tile2);
+    /// Returns a copy of the tile rotated 180°
+    ///
+    /// Equivalent to `flipped(true, true)`
+    pub fn rotate_180(&self) -> Tile {
+        self.flipped(true, true)
     }
 
-    #[test]
-    fn test_planar_conversion_complex_pattern() {
+    /// Returns a copy of the tile rotated 270° clockwise (90° counter-clockwise)
+    ///
+    /// Pixel `(x, y)` moves to `(y, 7 - x)`
+    pub fn rotate_270_cw(&self) -> Tile {
         let mut tile = Tile::new();
-
-        // Create a checkerboard pattern with different colors
         for y in 0..8 {
             for x in 0..8 {
-                let color = ((x + y) % 16) as u8;
-                tile.set_pixel(x, y, color);
+                tile.pixels[7 - x][y] = self.pixels[y][x];
             }
         }
+        tile
+    }
 
-        let planar = tile.to_planar();
+    /// Returns a copy of the tile with a rotation and flips applied
+    ///
+    /// The rotation is applied first, then the horizontal flip, then the
+    /// vertical flip. Every one of the eight symmetries of a square tile can
+    /// be produced with `v_flip` false; `v_flip` is accepted so tilemap flip
+    /// flags can be passed through directly.
+    pub fn transform(&self, rotation: Rotation, h_flip: bool, v_flip: bool) -> Tile {
+        let rotated = match rotation {
+            Rotation::Cw0 => self.clone(),
+            Rotation::Cw90 => self.rotate_90_cw(),
+            Rotation::Cw180 => self.rotate_180(),
+            Rotation::Cw270 => self.rotate_270_cw(),
+        };
+        rotated.flipped(h_flip, v_flip)
+    }
 
-        // Round-trip conversion should preserve all pixels
-        let tile2 = Tile::from_planar(&planar);
-        assert_eq!(tile, tile2);
+    /// Returns the color indices as a flat 64-byte buffer in row-major order
+    ///
+    /// Pixel `(x, y)` is at index `y * 8 + x`
+    pub fn to_flat(&self) -> [u8; 64] {
+        let mut flat = [0u8; 64];
+        for (dst, src) in flat.chunks_exact_mut(8).zip(&self.pixels) {
+            dst.copy_from_slice(src);
+        }
+        flat
+    }
 
-        // Verify specific pixels
-        for y in 0..8 {
-            for x in 0..8 {
-                assert_eq!(
-                    tile2.get_pixel(x, y),
-                    ((x + y) % 16) as u8,
-                    "Mismatch at ({}, {})",
-                    x,
-                    y
-                );
+    /// Creates a tile from a flat 64-byte row-major buffer of color indices
+    ///
+    /// Values above 15 are clamped to 15
+    pub fn from_flat(data: &[u8; 64]) -> Self {
+        let mut tile = Tile::new();
+        for (row, src) in tile.pixels.iter_mut().zip(data.chunks_exact(8)) {
+            for (dst, &color) in row.iter_mut().zip(src) {
+                *dst = color.min(15);
             }
         }
+        tile
+    }
+
+    /// Creates a tile from a row-major slice of 64 color indices
+    ///
+    /// Values above 15 are clamped to 15. Returns None if the slice length is
+    /// not exactly 64
+    pub fn from_indices(indices: &[u8]) -> Option<Self> {
+        let indices: &[u8; 64] = indices.try_into().ok()?;
+        Some(Self::from_flat(indices))
+    }
+
+    /// Returns the color indices in row-major order (inverse of `from_indices()`)
+    pub fn to_indices(&self) -> [u8; 64] {
+        self.to_flat()
+    }
+
+    /// Converts the tile to 8bpp chunky format (64 bytes, one byte per pixel)
+    ///
+    /// Same layout as `to_flat()`: row-major, pixel `(x, y)` at `y * 8 + x`
+    pub fn to_8bpp(&self) -> [u8; 64] {
+        self.to_flat()
+    }
+
+    /// Creates a tile from 8bpp chunky data (64 bytes, one byte per pixel)
+    ///
+    /// Values above 15 are clamped to 15 (see `from_flat()`)
+    pub fn from_8bpp(data: &[u8; 64]) -> Self {
+        Self::from_flat(data)
+    }
+
+    /// Converts the tile to 4bpp planar format (32 bytes)
+    ///
+    /// The planar format organizes data into four 8-byte bit planes:
+    /// - Bytes 0-7: Bit Plane 0 (LSB of color index)
+    /// - Bytes 8-15: Bit Plane 1
+    /// - Bytes 16-23: Bit Plane 2
+    /// - Bytes 24-31: Bit Plane 3 (MSB of color index)
+    ///
+    /// Within each plane, each byte represents one row of 8 pixels,
+    /// with bit 7 being the leftmost pixel (MSB first)
+    pub fn to_planar(&self) -> [u8; 32] {
+        let mut planar = [0u8; 32];
+
+        for (y, row) in self.pixels.iter().enumerate() {
+            for (plane, byte) in pack_planar_row(row).into_iter().enumerate() {
+                planar[plane * 8 + y] = byte;
+            }
+        }
+
+        planar
+    }
+
+    /// Creates a tile from 4bpp planar format data (32 bytes)
+    ///
+    /// See `to_planar()` for format description
+    pub fn from_planar(data: &[u8; 32]) -> Self {
+        let mut tile = Tile::new();
+        tile.load_planar(data);
+        tile
+    }
+
+    /// Overwrites this tile's pixels from 4bpp planar format data (32 bytes)
+    ///
+    /// In-place counterpart of `from_planar()`, letting decode loops reuse a
+    /// single `Tile`
+    pub fn load_planar(&mut self, data: &[u8; 32]) {
+        for (y, row) in self.pixels.iter_mut().enumerate() {
+            *row = unpack_planar_row(&[data[y], data[8 + y], data[16 + y], data[24 + y]]);
+        }
+    }
+
+    /// Creates a tile from a 4bpp planar byte slice
+    ///
+    /// Like `from_planar()`, but accepts a slice of any length and returns
+    /// `SemitileError::InvalidLength` unless it is exactly 32 bytes
+    pub fn try_from_planar_slice(data: &[u8]) -> Result<Self, SemitileError> {
+        let arr: &[u8; 32] = data.try_into().map_err(|_| SemitileError::InvalidLength {
+            expected: 32,
+            actual: data.len(),
+        })?;
+        Ok(Self::from_planar(arr))
+    }
+
+    /// Converts the tile to chunky (linear) 4bpp format (32 bytes)
+    ///
+    /// Each byte holds two horizontally adjacent pixels, rows in order:
+    /// - High nibble (bits 4-7): left pixel
+    /// - Low nibble (bits 0-3): right pixel
+    ///
+    /// Byte `y * 4 + x / 2` holds pixels `x` and `x + 1` (for even `x`) of row `y`
+    pub fn to_chunky_4bpp(&self) -> [u8; 32] {
+        let mut chunky = [0u8; 32];
+        for y in 0..8 {
+            for pair in 0..4 {
+                let left = self.pixels[y][pair * 2];
+                let right = self.pixels[y][pair * 2 + 1];
+                chunky[y * 4 + pair] = (left << 4) | right;
+            }
+        }
+        chunky
+    }
+
+    /// Creates a tile from chunky (linear) 4bpp format data (32 bytes)
+    ///
+    /// See `to_chunky_4bpp()` for format description
+    pub fn from_chunky_4bpp(data: &[u8; 32]) -> Self {
+        let mut tile = Tile::new();
+        for y in 0..8 {
+            for pair in 0..4 {
+                let byte = data[y * 4 + pair];
+                tile.pixels[y][pair * 2] = byte >> 4;
+                tile.pixels[y][pair * 2 + 1] = byte & 0x0F;
+            }
+        }
+        tile
+    }
+
+    /// Converts the tile to packed (linear) 4bpp format (32 bytes)
+    ///
+    /// Nibble order: the high nibble (bits 4-7) of each byte is the left pixel
+    /// and the low nibble (bits 0-3) the right pixel, rows in order. This is
+    /// the same layout as `to_chunky_4bpp()`.
+    pub fn to_packed_4bpp(&self) -> [u8; 32] {
+        self.to_chunky_4bpp()
+    }
+
+    /// Creates a tile from packed (linear) 4bpp data (32 bytes)
+    ///
+    /// See `to_packed_4bpp()` for the nibble order
+    pub fn from_packed_4bpp(data: &[u8; 32]) -> Self {
+        Self::from_chunky_4bpp(data)
+    }
+
+    /// Converts the tile to the GameBoy-style 2bpp interleaved format (16 bytes)
+    ///
+    /// Each row is two consecutive bytes: the low bit plane then the high bit
+    /// plane, with the leftmost pixel in the most significant bit. Only the low
+    /// two bits of each color index are kept; the upper two planes of the 4bpp
+    /// color are discarded, so indices above 3 do not survive the conversion.
+    pub fn to_2bpp(&self) -> [u8; 16] {
+        let mut data = [0u8; 16];
+        for (y, row) in self.pixels.iter().enumerate() {
+            for (x, &color) in row.iter().enumerate() {
+                let bit = 7 - x;
+                data[y * 2] |= (color & 1) << bit;
+                data[y * 2 + 1] |= ((color >> 1) & 1) << bit;
+            }
+        }
+        data
+    }
+
+    /// Creates a tile from GameBoy-style 2bpp interleaved data (16 bytes)
+    ///
+    /// See `to_2bpp()` for the format; the resulting color indices are 0-3
+    pub fn from_2bpp(data: &[u8; 16]) -> Self {
+        let mut tile = Tile::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                let bit = 7 - x;
+                let low = (data[y * 2] >> bit) & 1;
+                let high = (data[y * 2 + 1] >> bit) & 1;
+                tile.pixels[y][x] = (high << 1) | low;
+            }
+        }
+        tile
+    }
+
+    /// Returns a copy of the tile after a `to_planar()` → `from_planar()` round-trip
+    pub fn reencode(&self) -> Tile {
+        Tile::from_planar(&self.to_planar())
+    }
+
+    /// Returns true if the tile survives a planar round-trip unchanged
+    ///
+    /// Intended for fuzz and CI harnesses asserting lossless planar conversion
+    pub fn validate_planar_roundtrip(&self) -> bool {
+        self.reencode() == *self
+    }
+
+    /// Renders the tile to RGBA8888 pixel data (256 bytes)
+    ///
+    /// # Arguments
+    /// * `palette` - Palette to look colors up in
+    /// * `sub` - Sub-palette index (0-15) used for every pixel
+    ///
+    /// Pixels are written in row-major order, 4 bytes each, fully opaque
+    pub fn render_rgba(&self, palette: &Palette, sub: u8) -> [u8; 256] {
+        self.render_rgba_with(palette, sub, &RenderOptions::default())
+    }
+
+    /// Renders the tile to RGBA8888 pixel data (256 bytes) with extra options
+    ///
+    /// Same as `render_rgba()` unless `options` changes the output; see
+    /// `RenderOptions` for what each option does
+    pub fn render_rgba_with(
+        &self,
+        palette: &Palette,
+        sub: u8,
+        options: &RenderOptions,
+    ) -> [u8; 256] {
+        let mut rgba = [0u8; 256];
+        for y in 0..8 {
+            for x in 0..8 {
+                let mut color = palette.get_color(sub, self.pixels[y][x]);
+                if let Some(fallback) = options.fallback_color
+                    && color == Color::default()
+                {
+                    color = fallback;
+                }
+                let (r, g, b) = color.to_rgb888();
+                let offset = (y * 8 + x) * 4;
+                rgba[offset..offset + 4].copy_from_slice(&[r, g, b, 255]);
+            }
+        }
+        rgba
+    }
+
+    /// Converts the tile to an 8×8 `image::RgbaImage`
+    ///
+    /// Pixels match `render_rgba()`, so the result can be resized or saved to
+    /// any format with the `image` crate
+    #[cfg(feature = "image")]
+    pub fn to_image_buffer(&self, palette: &Palette, sub: u8) -> image::RgbaImage {
+        let rgba = self.render_rgba(palette, sub);
+        image::RgbaImage::from_fn(8, 8, |x, y| {
+            let offset = (y as usize * 8 + x as usize) * 4;
+            image::Rgba([
+                rgba[offset],
+                rgba[offset + 1],
+                rgba[offset + 2],
+                rgba[offset + 3],
+            ])
+        })
+    }
+
+    /// Renders the tile as a grayscale height map to RGBA8888 (256 bytes)
+    ///
+    /// # Arguments
+    /// * `height_for_index` - Height (0-255) assigned to each color index
+    ///
+    /// Each pixel is drawn as an opaque gray whose level is its index's height,
+    /// so higher areas appear brighter. Useful for previewing height data
+    /// encoded in tiles for pseudo-3D effects.
+    pub fn render_height_rgba(&self, height_for_index: &[u8; 16]) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(256);
+        for &index in self.pixels.iter().flatten() {
+            let level = height_for_index[index as usize];
+            rgba.extend_from_slice(&[level, level, level, 255]);
+        }
+        rgba
+    }
+
+    /// Renders the tile to RGBA8888 upscaled by an integer factor
+    ///
+    /// # Arguments
+    /// * `palette` - Palette to look colors up in
+    /// * `sub` - Sub-palette index (0-15) used for every pixel
+    /// * `scale` - Integer scale factor (0 is treated as 1)
+    ///
+    /// Returns a `(8 * scale)×(8 * scale)` row-major buffer where every source
+    /// pixel becomes a `scale`×`scale` block (nearest-neighbor)
+    pub fn render_rgba_scaled(&self, palette: &Palette, sub: u8, scale: usize) -> Vec<u8> {
+        let scale = scale.max(1);
+        let size = 8 * scale;
+        let base = self.render_rgba(palette, sub);
+        let mut rgba = Vec::with_capacity(size * size * 4);
+        for y in 0..size {
+            for x in 0..size {
+                let offset = ((y / scale) * 8 + x / scale) * 4;
+                rgba.extend_from_slice(&base[offset..offset + 4]);
+            }
+        }
+        rgba
+    }
+
+    /// Renders the tile to RGBA8888 upscaled with bilinear interpolation
+    ///
+    /// # Arguments
+    /// * `palette` - Palette to look colors up in
+    /// * `sub` - Sub-palette index (0-15) used for every pixel
+    /// * `scale` - Integer scale factor (0 is treated as 1)
+    /// * `wrap` - Blend edge pixels with the opposite edge (for repeating
+    ///   backgrounds) instead of clamping to the border
+    ///
+    /// Returns a `(8 * scale)×(8 * scale)` row-major buffer. Unlike
+    /// `render_rgba_scaled()`, neighboring pixels are blended for a softer look.
+    pub fn render_rgba_smooth(
+        &self,
+        palette: &Palette,
+        sub: u8,
+        scale: usize,
+        wrap: bool,
+    ) -> Vec<u8> {
+        let scale = scale.max(1);
+        let size = 8 * scale;
+        let base = self.render_rgba(palette, sub);
+
+        // Maps an output coordinate to the two source pixels and the blend weight
+        let sample = |o: usize| {
+            let s = (o as f32 + 0.5) / scale as f32 - 0.5;
+            let s0 = s.floor();
+            let t = s - s0;
+            let s0 = s0 as isize;
+            if wrap {
+                (
+                    s0.rem_euclid(8) as usize,
+                    (s0 + 1).rem_euclid(8) as usize,
+                    t,
+                )
+            } else {
+                (s0.clamp(0, 7) as usize, (s0 + 1).clamp(0, 7) as usize, t)
+            }
+        };
+
+        let mut rgba = Vec::with_capacity(size * size * 4);
+        for oy in 0..size {
+            let (y0, y1, ty) = sample(oy);
+            for ox in 0..size {
+                let (x0, x1, tx) = sample(ox);
+                let texel = |x: usize, y: usize, c: usize| base[(y * 8 + x) * 4 + c] as f32;
+                for c in 0..3 {
+                    let top = texel(x0, y0, c) * (1.0 - tx) + texel(x1, y0, c) * tx;
+                    let bottom = texel(x0, y1, c) * (1.0 - tx) + texel(x1, y1, c) * tx;
+                    rgba.push((top * (1.0 - ty) + bottom * ty).round() as u8);
+                }
+                rgba.push(255);
+            }
+        }
+        rgba
+    }
+
+    /// Renders the tile to RGBA8888 with transparency shown as a checkerboard
+    ///
+    /// Color index 0 is treated as transparent and composited over a gray
+    /// checkerboard of 2×2 squares, like image editors do. Opaque pixels use
+    /// `palette`/`sub` as in `render_rgba()`.
+    pub fn render_rgba_checkered(&self, palette: &Palette, sub: u8) -> [u8; 256] {
+        let mut rgba = self.render_rgba(palette, sub);
+        for y in 0..8 {
+            for x in 0..8 {
+                if self.pixels[y][x] == 0 {
+                    let checker = CHECKER_COLORS[(x / CHECKER_CELL + y / CHECKER_CELL) % 2];
+                    let offset = (y * 8 + x) * 4;
+                    rgba[offset..offset + 4].copy_from_slice(&checker);
+                }
+            }
+        }
+        rgba
+    }
+
+    /// Renders a pixel-level diff against another tile to RGBA8888 (256 bytes)
+    ///
+    /// Pixels that match `other` are rendered normally using `palette`/`sub`,
+    /// while differing pixels are drawn in magenta (255, 0, 255)
+    pub fn render_diff_rgba(&self, other: &Tile, palette: &Palette, sub: u8) -> [u8; 256] {
+        let mut rgba = self.render_rgba(palette, sub);
+        for y in 0..8 {
+            for x in 0..8 {
+                if self.pixels[y][x] != other.pixels[y][x] {
+                    let offset = (y * 8 + x) * 4;
+                    rgba[offset..offset + 4].copy_from_slice(&DIFF_COLOR);
+                }
+            }
+        }
+        rgba
+    }
+}
+
+impl Default for Tile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hash for Tile {
+    /// Hashes the 32-byte planar encoding, which is unique per pixel layout
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_planar().hash(state);
+    }
+}
+
+impl From<[[u8; 8]; 8]> for Tile {
+    /// Builds a tile from 8 rows of color indices; values above 15 are clamped
+    fn from(rows: [[u8; 8]; 8]) -> Self {
+        let mut tile = Tile::new();
+        for (y, row) in rows.iter().enumerate() {
+            tile.set_row(y, row);
+        }
+        tile
+    }
+}
+
+impl From<Tile> for [[u8; 8]; 8] {
+    /// Returns the 8 rows of color indices
+    fn from(tile: Tile) -> Self {
+        tile.pixels
+    }
+}
+
+impl From<&Tile> for [u8; 32] {
+    /// Same as `Tile::to_planar()`
+    fn from(tile: &Tile) -> Self {
+        tile.to_planar()
+    }
+}
+
+impl From<[u8; 32]> for Tile {
+    /// Same as `Tile::from_planar()`
+    fn from(data: [u8; 32]) -> Self {
+        Tile::from_planar(&data)
+    }
+}
+
+/// Packs one row of 8 color indices into its four planar bytes
+///
+/// Returns `[plane0, plane1, plane2, plane3]`, where bit `n` of a color index
+/// goes to plane `n` and bit 7 of each byte is the leftmost pixel. Only the
+/// low four bits of each index are used. This is the per-row step behind
+/// `Tile::to_planar()`.
+pub fn pack_planar_row(indices: &[u8; 8]) -> [u8; 4] {
+    let mut planes = [0u8; 4];
+    for (x, &color) in indices.iter().enumerate() {
+        let bit_pos = 7 - x; // MSB first (bit 7 = leftmost pixel)
+        for (plane, byte) in planes.iter_mut().enumerate() {
+            *byte |= ((color >> plane) & 1) << bit_pos;
+        }
+    }
+    planes
+}
+
+/// Unpacks four planar bytes into one row of 8 color indices
+///
+/// Inverse of `pack_planar_row()`
+pub fn unpack_planar_row(planes: &[u8; 4]) -> [u8; 8] {
+    let mut indices = [0u8; 8];
+    for (x, color) in indices.iter_mut().enumerate() {
+        let bit_pos = 7 - x;
+        for (plane, &byte) in planes.iter().enumerate() {
+            *color |= ((byte >> bit_pos) & 1) << plane;
+        }
+    }
+    indices
+}
+
+/// Decodes tiles one at a time from a 4bpp planar dump (32 bytes per tile)
+///
+/// Nothing is collected, so arbitrarily large CHR data can be processed with
+/// constant memory. A trailing chunk shorter than 32 bytes yields a final
+/// `SemitileError::InvalidLength` item.
+pub fn iter_tiles_from_planar(
+    data: &[u8],
+) -> impl Iterator<Item = Result<Tile, SemitileError>> + '_ {
+    data.chunks(32).map(Tile::try_from_planar_slice)
+}
+
+/// Clips a line segment to the 8×8 tile using Liang–Barsky
+///
+/// Returns the clipped endpoints rounded to the nearest pixel, or None if the
+/// segment misses the tile entirely. Deltas are computed in i64 so extreme
+/// `i32` endpoints cannot overflow.
+fn clip_line(x0: i32, y0: i32, x1: i32, y1: i32) -> Option<(i64, i64, i64, i64)> {
+    let dx = (x1 as i64 - x0 as i64) as f64;
+    let dy = (y1 as i64 - y0 as i64) as f64;
+    let (x0, y0) = (x0 as f64, y0 as f64);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for (p, q) in [(-dx, x0), (dx, 7.0 - x0), (-dy, y0), (dy, 7.0 - y0)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let r = q / p;
+            if p < 0.0 {
+                if r > t1 {
+                    return None;
+                }
+                t0 = t0.max(r);
+            } else {
+                if r < t0 {
+                    return None;
+                }
+                t1 = t1.min(r);
+            }
+        }
+    }
+    let point = |t: f64| {
+        let x = (x0 + t * dx).round().clamp(0.0, 7.0) as i64;
+        let y = (y0 + t * dy).round().clamp(0.0, 7.0) as i64;
+        (x, y)
+    };
+    let ((cx0, cy0), (cx1, cy1)) = (point(t0), point(t1));
+    Some((cx0, cy0, cx1, cy1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_tile_is_empty() {
+        let tile = Tile::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(tile.get_pixel(x, y), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_and_get_pixel() {
+        let mut tile = Tile::new();
+        tile.set_pixel(3, 4, 7);
+        assert_eq!(tile.get_pixel(3, 4), 7);
+        assert_eq!(tile.get_pixel(0, 0), 0);
+    }
+
+    #[test]
+    fn test_set_pixel_bounds_checking() {
+        let mut tile = Tile::new();
+
+        // Out of bounds - should be ignored
+        tile.set_pixel(8, 0, 5);
+        tile.set_pixel(0, 8, 5);
+        tile.set_pixel(10, 10, 5);
+
+        // Color too large - should be ignored
+        tile.set_pixel(0, 0, 16);
+        assert_eq!(tile.get_pixel(0, 0), 0);
+    }
+
+    #[test]
+    fn test_get_pixel_out_of_bounds() {
+        let tile = Tile::new();
+        assert_eq!(tile.get_pixel(8, 0), 0);
+        assert_eq!(tile.get_pixel(0, 8), 0);
+        assert_eq!(tile.get_pixel(100, 100), 0);
+    }
+
+    #[test]
+    fn test_planar_conversion_empty_tile() {
+        let tile = Tile::new();
+        let planar = tile.to_planar();
+
+        // All bytes should be 0 for an empty tile
+        for byte in planar.iter() {
+            assert_eq!(*byte, 0);
+        }
+
+        // Round-trip conversion
+        let tile2 = Tile::from_planar(&planar);
+        assert_eq!(tile, tile2);
+    }
+
+    #[test]
+    fn test_planar_conversion_single_pixel() {
+        let mut tile = Tile::new();
+        tile.set_pixel(0, 0, 0b1111); // Color 15 at top-left
+
+        let planar = tile.to_planar();
+
+        // Top-left pixel (bit 7) should be set in all four planes
+        assert_eq!(planar[0], 0b10000000); // Plane 0, row 0
+        assert_eq!(planar[8], 0b10000000); // Plane 1, row 0
+        assert_eq!(planar[16], 0b10000000); // Plane 2, row 0
+        assert_eq!(planar[24], 0b10000000); // Plane 3, row 0
+
+        // Round-trip conversion
+        let tile2 = Tile::from_planar(&planar);
+        assert_eq!(tile, tile2);
+    }
+
+    #[test]
+    fn test_planar_conversion_single_pixel_color_5() {
+        let mut tile = Tile::new();
+        tile.set_pixel(7, 0, 0b0101); // Color 5 at top-right
+
+        let planar = tile.to_planar();
+
+        // Top-right pixel (bit 0) should be set in planes 0 and 2 only
+        // Color 5 = 0b0101 = planes 0 and 2
+        assert_eq!(planar[0], 0b00000001); // Plane 0, row 0
+        assert_eq!(planar[8], 0b00000000); // Plane 1, row 0
+        assert_eq!(planar[16], 0b00000001); // Plane 2, row 0
+        assert_eq!(planar[24], 0b00000000); // Plane 3, row 0
+
+        // Round-trip conversion
+        let tile2 = Tile::from_planar(&planar);
+        assert_eq!(tile, tile2);
+    }
+
+    #[test]
+    fn test_planar_conversion_full_row() {
+        let mut tile = Tile::new();
+        // Set first row to alternating colors
+        for x in 0..8 {
+            tile.set_pixel(x, 0, (x % 2) as u8);
+        }
+
+        let planar = tile.to_planar();
+
+        // First row should be 0b01010101 in plane 0, 0 in others
+        assert_eq!(planar[0], 0b01010101);
+        assert_eq!(planar[8], 0b00000000);
+        assert_eq!(planar[16], 0b00000000);
+        assert_eq!(planar[24], 0b00000000);
+
+        // Round-trip conversion
+        let tile2 = Tile::from_planar(&planar);
+        assert_eq!(tile, tile2);
+    }
+
+    #[test]
+    fn test_planar_conversion_complex_pattern() {
+        let mut tile = Tile::new();
+
+        // Create a checkerboard pattern with different colors
+        for y in 0..8 {
+            for x in 0..8 {
+                let color = ((x + y) % 16) as u8;
+                tile.set_pixel(x, y, color);
+            }
+        }
+
+        let planar = tile.to_planar();
+
+        // Round-trip conversion should preserve all pixels
+        let tile2 = Tile::from_planar(&planar);
+        assert_eq!(tile, tile2);
+
+        // Verify specific pixels
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(
+                    tile2.get_pixel(x, y),
+                    ((x + y) % 16) as u8,
+                    "Mismatch at ({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_planar_all_colors() {
+        // Test all 16 colors to ensure bit manipulation is correct
+        for color in 0..16 {
+            let mut tile = Tile::new();
+            tile.set_pixel(3, 3, color);
+
+            let planar = tile.to_planar();
+            let tile2 = Tile::from_planar(&planar);
+
+            assert_eq!(
+                tile2.get_pixel(3, 3),
+                color,
+                "Round-trip failed for color {}",
+                color
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_rgba() {
+        let mut palette = Palette::new();
+        palette.set_color(1, 2, Color::new(31, 0, 0));
+
+        let mut tile = Tile::new();
+        tile.set_pixel(1, 0, 2);
+
+        let rgba = tile.render_rgba(&palette, 1);
+        assert_eq!(&rgba[0..4], &[0, 0, 0, 255]);
+        assert_eq!(&rgba[4..8], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_render_diff_rgba_single_pixel() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 1, Color::new(0, 31, 0));
+
+        let mut tile = Tile::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                tile.set_pixel(x, y, 1);
+            }
+        }
+        let mut other = tile.clone();
+        other.set_pixel(5, 6, 0);
+
+        let rgba = tile.render_diff_rgba(&other, &palette, 0);
+        let magenta = rgba.chunks(4).filter(|px| *px == DIFF_COLOR).count();
+        assert_eq!(magenta, 1);
+        let offset = (6 * 8 + 5) * 4;
+        assert_eq!(&rgba[offset..offset + 4], &DIFF_COLOR);
+        assert_eq!(&rgba[0..4], &[0, 255, 0, 255]);
+
+        // Identical tiles have no highlighted pixels
+        assert_eq!(
+            tile.render_diff_rgba(&tile, &palette, 0),
+            tile.render_rgba(&palette, 0)
+        );
+    }
+
+    #[test]
+    fn test_validate_planar_roundtrip_random_tiles() {
+        // Simple LCG so the test is reproducible without an rng dependency
+        let mut state = 0x1234_5678u32;
+        for _ in 0..256 {
+            let mut tile = Tile::new();
+            for y in 0..8 {
+                for x in 0..8 {
+                    state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    tile.set_pixel(x, y, (state >> 28) as u8);
+                }
+            }
+            assert!(tile.validate_planar_roundtrip());
+            assert_eq!(tile.reencode(), tile);
+        }
+    }
+
+    #[test]
+    fn test_render_rgba_checkered() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 0, Color::new(31, 0, 0)); // Ignored: index 0 is transparent
+        palette.set_color(0, 3, Color::new(0, 0, 31));
+
+        let mut tile = Tile::new();
+        tile.set_pixel(7, 7, 3);
+
+        let rgba = tile.render_rgba_checkered(&palette, 0);
+        let pixel = |x: usize, y: usize| &rgba[(y * 8 + x) * 4..(y * 8 + x) * 4 + 4];
+
+        assert_eq!(pixel(0, 0), &CHECKER_COLORS[0]);
+        assert_eq!(pixel(1, 1), &CHECKER_COLORS[0]);
+        assert_eq!(pixel(2, 0), &CHECKER_COLORS[1]);
+        assert_eq!(pixel(0, 2), &CHECKER_COLORS[1]);
+        assert_eq!(pixel(2, 2), &CHECKER_COLORS[0]);
+        assert_eq!(pixel(7, 7), &[0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_flat_round_trip() {
+        let mut tile = Tile::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                tile.set_pixel(x, y, ((x * 3 + y) % 16) as u8);
+            }
+        }
+
+        let flat = tile.to_flat();
+        assert_eq!(flat[3 * 8 + 5], tile.get_pixel(5, 3));
+        assert_eq!(Tile::from_flat(&flat), tile);
+    }
+
+    #[test]
+    fn test_from_flat_clamps() {
+        let mut data = [0u8; 64];
+        data[9] = 200;
+        let tile = Tile::from_flat(&data);
+        assert_eq!(tile.get_pixel(1, 1), 15);
+    }
+
+    #[test]
+    fn test_flip_h_and_v() {
+        let mut tile = Tile::new();
+        tile.set_pixel(1, 2, 7);
+
+        let mut h = tile.clone();
+        h.flip_h();
+        assert_eq!(h.get_pixel(6, 2), 7);
+        assert_eq!(h.get_pixel(1, 2), 0);
+
+        let mut v = tile.clone();
+        v.flip_v();
+        assert_eq!(v.get_pixel(1, 5), 7);
+        assert_eq!(v.get_pixel(1, 2), 0);
+    }
+
+    #[test]
+    fn test_flip_variants() {
+        let mut tile = Tile::new();
+        tile.set_pixel(0, 0, 1);
+        tile.set_pixel(1, 0, 2);
+
+        let variants = tile.flip_variants();
+        assert_eq!(variants[0], (tile.clone(), false, false));
+        assert_eq!((variants[1].1, variants[1].2), (true, false));
+        assert_eq!((variants[2].1, variants[2].2), (false, true));
+        assert_eq!((variants[3].1, variants[3].2), (true, true));
+
+        assert_eq!(variants[1].0.get_pixel(7, 0), 1);
+        assert_eq!(variants[1].0.get_pixel(6, 0), 2);
+        assert_eq!(variants[2].0.get_pixel(0, 7), 1);
+        assert_eq!(variants[3].0.get_pixel(7, 7), 1);
+        assert_eq!(variants[3].0.get_pixel(6, 7), 2);
+
+        for (variant, h_flip, v_flip) in &variants {
+            assert_eq!(&tile.flipped(*h_flip, *v_flip), variant);
+            // Flips are involutions, so applying them again restores the original
+            assert_eq!(variant.flipped(*h_flip, *v_flip), tile);
+        }
+    }
+
+    #[test]
+    fn test_paint_radius_one() {
+        let mut tile = Tile::new();
+        tile.paint(3, 3, 1, 6);
+
+        for y in 0..8 {
+            for x in 0..8 {
+                let inside = (2..=4).contains(&x) && (2..=4).contains(&y);
+                assert_eq!(
+                    tile.get_pixel(x, y),
+                    if inside { 6 } else { 0 },
+                    "({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_paint_clips_and_validates() {
+        let mut tile = Tile::new();
+        tile.paint(0, 7, 2, 4);
+        let painted = (0..8)
+            .flat_map(|y| (0..8).map(move |x| (x, y)))
+            .filter(|&(x, y)| tile.get_pixel(x, y) == 4)
+            .count();
+        assert_eq!(painted, 3 * 3); // x 0-2, y 5-7
+
+        let before = tile.clone();
+        tile.paint(3, 3, 1, 16); // Invalid color
+        tile.paint(20, 20, 1, 1); // Entirely out of bounds
+        assert_eq!(tile, before);
+    }
+
+    #[test]
+    fn test_pseudo_random_is_deterministic() {
+        assert_eq!(Tile::pseudo_random(42), Tile::pseudo_random(42));
+        assert_ne!(Tile::pseudo_random(42), Tile::pseudo_random(43));
+
+        // Content is actually varied, not a constant fill
+        let tile = Tile::pseudo_random(7);
+        let distinct: std::collections::HashSet<u8> =
+            (0..64).map(|i| tile.get_pixel(i % 8, i / 8)).collect();
+        assert!(distinct.len() > 4);
+    }
+
+    #[test]
+    fn test_render_rgba_scaled() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 1, Color::new(31, 31, 31));
+
+        let mut tile = Tile::new();
+        tile.set_pixel(1, 0, 1);
+
+        let rgba = tile.render_rgba_scaled(&palette, 0, 2);
+        assert_eq!(rgba.len(), 16 * 16 * 4);
+
+        let pixel = |x: usize, y: usize| &rgba[(y * 16 + x) * 4..(y * 16 + x) * 4 + 4];
+        for (x, y) in [(2, 0), (3, 0), (2, 1), (3, 1)] {
+            assert_eq!(pixel(x, y), &[255, 255, 255, 255]);
+        }
+        assert_eq!(pixel(1, 0), &[0, 0, 0, 255]);
+        assert_eq!(pixel(4, 0), &[0, 0, 0, 255]);
+        assert_eq!(pixel(2, 2), &[0, 0, 0, 255]);
+
+        // Scale 1 matches the plain render
+        assert_eq!(
+            tile.render_rgba_scaled(&palette, 0, 1),
+            tile.render_rgba(&palette, 0).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_render_rgba_smooth_blends_edge() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 1, Color::new(31, 31, 31));
+
+        // Left half black, right half white
+        let mut tile = Tile::new();
+        for y in 0..8 {
+            for x in 4..8 {
+                tile.set_pixel(x, y, 1);
+            }
+        }
+
+        let scale = 4;
+        let rgba = tile.render_rgba_smooth(&palette, 0, scale, false);
+        assert_eq!(rgba.len(), 32 * 32 * 4);
+
+        let red = |x: usize| rgba[(5 * 32 + x) * 4];
+        // Far from the edge the colors are pure
+        assert_eq!(red(0), 0);
+        assert_eq!(red(31), 255);
+        // Around the black/white boundary (x = 16) the colors are intermediate
+        let edge: Vec<u8> = (13..19).map(red).collect();
+        assert!(edge.iter().any(|&v| v > 0 && v < 255), "{:?}", edge);
+        assert!(edge.windows(2).all(|w| w[0] <= w[1]));
+
+        // With wrapping the left border blends with the white right edge
+        let wrapped = tile.render_rgba_smooth(&palette, 0, scale, true);
+        let first = wrapped[5 * 32 * 4];
+        assert!(first > 0 && first < 255);
+    }
+
+    #[test]
+    fn test_chunky_4bpp_nibble_order() {
+        let mut tile = Tile::new();
+        for (x, color) in [1, 2, 3, 4, 5, 6, 7, 15].into_iter().enumerate() {
+            tile.set_pixel(x, 0, color);
+        }
+
+        let chunky = tile.to_chunky_4bpp();
+        // Left pixel in the high nibble
+        assert_eq!(&chunky[0..4], &[0x12, 0x34, 0x56, 0x7F]);
+        assert!(chunky[4..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_chunky_4bpp_round_trip() {
+        let tile = Tile::pseudo_random(237);
+        assert_eq!(Tile::from_chunky_4bpp(&tile.to_chunky_4bpp()), tile);
     }
 
     #[test]
-    fn test_planar_all_colors() {
-        // Test all 16 colors to ensure bit manipulation is correct
-        for color in 0..16 {
-            let mut tile = Tile::new();
-            tile.set_pixel(3, 3, color);
+    fn test_try_from_planar_slice() {
+        let mut tile = Tile::new();
+        tile.set_pixel(3, 5, 9);
+        let planar = tile.to_planar();
 
-            let planar = tile.to_planar();
-            let tile2 = Tile::from_planar(&planar);
+        assert_eq!(Tile::try_from_planar_slice(&planar), Ok(tile));
+        assert_eq!(
+            Tile::try_from_planar_slice(&planar[..31]),
+            Err(SemitileError::InvalidLength {
+                expected: 32,
+                actual: 31
+            })
+        );
+        assert_eq!(
+            Tile::try_from_planar_slice(&[0u8; 40]),
+            Err(SemitileError::InvalidLength {
+                expected: 32,
+                actual: 40
+            })
+        );
+    }
+
+    #[test]
+    fn test_tile_render_rgba_with_fallback_color() {
+        let mut palette = Palette::new();
+        palette.set_color(0, 1, Color::new(31, 0, 0));
+        let mut tile = Tile::new();
+        tile.set_pixel(1, 0, 1);
+
+        // Without a fallback the output matches the plain render
+        let plain = tile.render_rgba_with(&palette, 0, &RenderOptions::default());
+        assert_eq!(plain, tile.render_rgba(&palette, 0));
+        assert_eq!(&plain[0..4], &[0, 0, 0, 255]);
+
+        let options = RenderOptions {
+            fallback_color: Some(Color::new(31, 0, 31)),
+        };
+        let rgba = tile.render_rgba_with(&palette, 0, &options);
+        assert_eq!(&rgba[0..4], &[255, 0, 255, 255]); // Index 0 is an undefined black slot
+        assert_eq!(&rgba[4..8], &[255, 0, 0, 255]); // Defined colors are untouched
+    }
+
+    #[test]
+    fn test_iter_tiles_from_planar() {
+        let tiles: Vec<Tile> = (0..3).map(Tile::pseudo_random).collect();
+        let mut data: Vec<u8> = tiles.iter().flat_map(|tile| tile.to_planar()).collect();
+
+        let decoded: Vec<_> = iter_tiles_from_planar(&data).collect();
+        assert_eq!(decoded, tiles.iter().cloned().map(Ok).collect::<Vec<_>>());
+
+        data.extend_from_slice(&[0xAA; 5]);
+        let decoded: Vec<_> = iter_tiles_from_planar(&data).collect();
+        assert_eq!(decoded.len(), 4);
+        assert!(decoded[..3].iter().all(Result::is_ok));
+        assert_eq!(
+            decoded[3],
+            Err(SemitileError::InvalidLength {
+                expected: 32,
+                actual: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_tile_load_planar_matches_from_planar() {
+        let mut tile = Tile::pseudo_random(1);
+        for seed in 2..6 {
+            let planar = Tile::pseudo_random(seed).to_planar();
+            tile.load_planar(&planar);
+            assert_eq!(tile, Tile::from_planar(&planar));
+        }
+    }
+
+    #[test]
+    fn test_tile_rotate_90_cw_mapping() {
+        let tile = Tile::pseudo_random(7);
+        let rotated = tile.rotate_90_cw();
+        for y in 0..8 {
+            for x in 0..8 {
+                // Inverse mapping: destination (x, y) came from source (y, 7 - x)
+                assert_eq!(rotated.get_pixel(x, y), tile.get_pixel(y, 7 - x));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tile_rotations_compose() {
+        let tile = Tile::pseudo_random(11);
+        assert_eq!(
+            tile.rotate_90_cw()
+                .rotate_90_cw()
+                .rotate_90_cw()
+                .rotate_90_cw(),
+            tile
+        );
+        assert_eq!(tile.rotate_180(), tile.rotate_90_cw().rotate_90_cw());
+        assert_eq!(tile.rotate_180(), tile.flipped(true, true));
+        assert_eq!(tile.rotate_270_cw(), tile.rotate_180().rotate_90_cw());
+        assert_eq!(tile.rotate_270_cw().rotate_90_cw(), tile);
+    }
+
+    #[test]
+    fn test_tile_transpose() {
+        let tile = Tile::pseudo_random(3);
+        let transposed = tile.transposed();
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(transposed.get_pixel(x, y), tile.get_pixel(y, x));
+            }
+        }
+        assert_eq!(transposed.transposed(), tile);
+
+        // Transpose followed by a horizontal flip is a 90° clockwise rotation
+        assert_eq!(tile.transposed().flipped(true, false), tile.rotate_90_cw());
+        assert_eq!(tile.transposed().flipped(false, true), tile.rotate_270_cw());
+    }
+
+    #[test]
+    fn test_tile_flood_fill_diagonals_not_connected() {
+        let mut tile = Tile::new();
+        for i in 0..8 {
+            tile.set_pixel(i, i, 5);
+        }
+        tile.flood_fill(0, 0, 9);
+
+        assert_eq!(tile.get_pixel(0, 0), 9);
+        for i in 1..8 {
+            assert_eq!(tile.get_pixel(i, i), 5);
+        }
+        // Background on either side of the stripe is a separate region too
+        tile.flood_fill(7, 0, 2);
+        assert_eq!(tile.get_pixel(1, 0), 2);
+        assert_eq!(tile.get_pixel(0, 1), 0);
+    }
+
+    #[test]
+    fn test_tile_flood_fill_full_tile_and_no_ops() {
+        let mut tile = Tile::new();
+        tile.flood_fill(3, 4, 6);
+        assert_eq!(tile.to_flat(), [6; 64]);
+
+        let before = tile.clone();
+        tile.flood_fill(3, 4, 6);
+        tile.flood_fill(8, 0, 1);
+        tile.flood_fill(0, 0, 16);
+        assert_eq!(tile, before);
+    }
+
+    fn touched_pixels(tile: &Tile) -> Vec<(usize, usize)> {
+        let mut touched = Vec::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                if tile.get_pixel(x, y) != 0 {
+                    touched.push((x, y));
+                }
+            }
+        }
+        touched
+    }
+
+    #[test]
+    fn test_tile_draw_line_horizontal_and_vertical() {
+        let mut tile = Tile::new();
+        tile.draw_line(6, 2, 1, 2, 3);
+        assert_eq!(
+            touched_pixels(&tile),
+            (1..=6).map(|x| (x, 2)).collect::<Vec<_>>()
+        );
+
+        let mut tile = Tile::new();
+        tile.draw_line(4, 0, 4, 3, 3);
+        assert_eq!(touched_pixels(&tile), vec![(4, 0), (4, 1), (4, 2), (4, 3)]);
+    }
+
+    #[test]
+    fn test_tile_draw_line_diagonal_and_steep() {
+        let mut tile = Tile::new();
+        tile.draw_line(7, 7, 0, 0, 1);
+        assert_eq!(
+            touched_pixels(&tile),
+            (0..8).map(|i| (i, i)).collect::<Vec<_>>()
+        );
+
+        let mut tile = Tile::new();
+        tile.draw_line(0, 7, 2, 0, 1);
+        assert_eq!(
+            touched_pixels(&tile),
+            vec![
+                (2, 0),
+                (2, 1),
+                (1, 2),
+                (1, 3),
+                (1, 4),
+                (1, 5),
+                (0, 6),
+                (0, 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tile_draw_line_clipping() {
+        let mut tile = Tile::new();
+        tile.draw_line(-3, 1, 10, 1, 4);
+        assert_eq!(
+            touched_pixels(&tile),
+            (0..8).map(|x| (x, 1)).collect::<Vec<_>>()
+        );
+
+        let mut tile = Tile::new();
+        tile.draw_line(0, 0, 7, 7, 16);
+        assert!(touched_pixels(&tile).is_empty());
+    }
+
+    #[test]
+    fn test_tile_draw_line_far_and_extreme_endpoints() {
+        let mut tile = Tile::new();
+        tile.draw_line(0, 0, 1_000_000_000, 0, 2);
+        assert_eq!(
+            touched_pixels(&tile),
+            (0..8).map(|x| (x, 0)).collect::<Vec<_>>()
+        );
+
+        let mut tile = Tile::new();
+        tile.draw_line(i32::MIN, 3, i32::MAX, 3, 2);
+        assert_eq!(
+            touched_pixels(&tile),
+            (0..8).map(|x| (x, 3)).collect::<Vec<_>>()
+        );
+
+        let mut tile = Tile::new();
+        tile.draw_line(i32::MIN, i32::MIN, i32::MAX, i32::MAX, 2);
+        assert_eq!(
+            touched_pixels(&tile),
+            (0..8).map(|i| (i, i)).collect::<Vec<_>>()
+        );
+
+        // Entirely outside: nothing drawn
+        let mut tile = Tile::new();
+        tile.draw_line(i32::MIN, -1, i32::MAX, -1, 2);
+        assert!(touched_pixels(&tile).is_empty());
+    }
+
+    #[test]
+    fn test_tile_fill_rect_and_draw_rect_inside() {
+        let mut tile = Tile::new();
+        tile.fill_rect(1, 2, 3, 2, 4);
+        let expected: Vec<_> = (2..4).flat_map(|y| (1..4).map(move |x| (x, y))).collect();
+        assert_eq!(touched_pixels(&tile), expected);
+
+        let mut tile = Tile::new();
+        tile.draw_rect(1, 1, 4, 3, 4);
+        assert_eq!(
+            touched_pixels(&tile),
+            vec![
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (1, 2),
+                (4, 2),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (4, 3)
+            ]
+        );
+    }
 
+    #[test]
+    fn test_tile_rect_clipped_at_corner() {
+        let mut tile = Tile::new();
+        tile.fill_rect(6, 5, 10, 10, 2);
+        let expected: Vec<_> = (5..8).flat_map(|y| (6..8).map(move |x| (x, y))).collect();
+        assert_eq!(touched_pixels(&tile), expected);
+
+        let mut tile = Tile::new();
+        tile.draw_rect(6, 5, 10, 10, 2);
+        // Only the top and left edges are visible
+        assert_eq!(touched_pixels(&tile), vec![(6, 5), (7, 5), (6, 6), (6, 7)]);
+    }
+
+    #[test]
+    fn test_tile_rect_single_pixel_and_invalid_color() {
+        let mut tile = Tile::new();
+        tile.fill_rect(3, 3, 1, 1, 7);
+        assert_eq!(touched_pixels(&tile), vec![(3, 3)]);
+
+        let mut tile = Tile::new();
+        tile.draw_rect(3, 3, 1, 1, 7);
+        assert_eq!(touched_pixels(&tile), vec![(3, 3)]);
+
+        tile.fill_rect(0, 0, 8, 8, 16);
+        tile.draw_rect(0, 0, 8, 8, 16);
+        assert_eq!(touched_pixels(&tile), vec![(3, 3)]);
+    }
+
+    #[test]
+    fn test_tile_transform_d4() {
+        let mut tile = Tile::new();
+        tile.set_pixel(1, 0, 1);
+
+        let cases = [
+            (Rotation::Cw0, false, (1, 0)),
+            (Rotation::Cw0, true, (6, 0)),
+            (Rotation::Cw90, false, (7, 1)),
+            (Rotation::Cw90, true, (0, 1)),
+            (Rotation::Cw180, false, (6, 7)),
+            (Rotation::Cw180, true, (1, 7)),
+            (Rotation::Cw270, false, (0, 6)),
+            (Rotation::Cw270, true, (7, 6)),
+        ];
+        for (rotation, h_flip, expected) in cases {
+            let transformed = tile.transform(rotation, h_flip, false);
             assert_eq!(
-                tile2.get_pixel(3, 3),
-                color,
-                "Round-trip failed for color {}",
-                color
+                touched_pixels(&transformed),
+                vec![expected],
+                "{:?} h={}",
+                rotation,
+                h_flip
             );
         }
+
+        // A vertical flip is a 180° rotation plus a horizontal flip
+        assert_eq!(
+            tile.transform(Rotation::Cw0, false, true),
+            tile.transform(Rotation::Cw180, true, false)
+        );
+        assert_eq!(tile.transform(Rotation::Cw0, true, true), tile.rotate_180());
+    }
+
+    #[test]
+    fn test_tile_replace_color() {
+        // Gradient: each pixel's color is x + y (0-14)
+        let mut tile = Tile::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                tile.set_pixel(x, y, (x + y) as u8);
+            }
+        }
+
+        assert_eq!(tile.replace_color(5, 9), 6);
+        for y in 0..8 {
+            for x in 0..8 {
+                let expected = if x + y == 5 { 9 } else { (x + y) as u8 };
+                assert_eq!(tile.get_pixel(x, y), expected);
+            }
+        }
+
+        let before = tile.clone();
+        assert_eq!(tile.replace_color(3, 3), 0);
+        assert_eq!(tile.replace_color(16, 0), 0);
+        assert_eq!(tile.replace_color(0, 16), 0);
+        assert_eq!(tile.replace_color(15, 1), 0);
+        assert_eq!(tile, before);
+    }
+
+    #[test]
+    fn test_tile_render_height_rgba() {
+        let mut tile = Tile::new();
+        tile.fill_rect(0, 0, 4, 8, 3);
+        let mut heights = [0u8; 16];
+        heights[0] = 40;
+        heights[3] = 200;
+
+        let rgba = tile.render_height_rgba(&heights);
+        assert_eq!(rgba.len(), 256);
+        assert_eq!(&rgba[0..4], &[200, 200, 200, 255]); // (0, 0) uses index 3
+        assert_eq!(&rgba[28..32], &[40, 40, 40, 255]); // (7, 0) uses index 0
+
+        let mut levels: Vec<u8> = rgba.chunks_exact(4).map(|px| px[0]).collect();
+        levels.sort_unstable();
+        levels.dedup();
+        assert_eq!(levels, vec![40, 200]);
+    }
+
+    #[test]
+    fn test_tile_swap_colors() {
+        let original = Tile::pseudo_random(21);
+        let mut tile = original.clone();
+        tile.swap_colors(1, 2);
+        for y in 0..8 {
+            for x in 0..8 {
+                let expected = match original.get_pixel(x, y) {
+                    1 => 2,
+                    2 => 1,
+                    other => other,
+                };
+                assert_eq!(tile.get_pixel(x, y), expected);
+            }
+        }
+        tile.swap_colors(2, 1);
+        assert_eq!(tile, original);
+
+        tile.swap_colors(4, 4);
+        tile.swap_colors(4, 16);
+        assert_eq!(tile, original);
+    }
+
+    #[test]
+    fn test_tile_swap_colors_one_index_present() {
+        let mut tile = Tile::new();
+        tile.fill_rect(0, 0, 8, 2, 5);
+        tile.swap_colors(5, 9);
+        assert_eq!(touched_pixels(&tile).len(), 16);
+        assert!(
+            touched_pixels(&tile)
+                .iter()
+                .all(|&(x, y)| tile.get_pixel(x, y) == 9)
+        );
+    }
+
+    #[test]
+    fn test_tile_remap_colors() {
+        let original = Tile::pseudo_random(5);
+
+        let mut tile = original.clone();
+        tile.remap_colors(&std::array::from_fn(|c| c as u8));
+        assert_eq!(tile, original);
+
+        tile.remap_colors(&std::array::from_fn(|c| 15 - c as u8));
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(tile.get_pixel(x, y), 15 - original.get_pixel(x, y));
+            }
+        }
+
+        // Collapse indices 0-7 to 0; the out-of-range entry for 15 clamps back to 15
+        let mut lut: [u8; 16] = std::array::from_fn(|c| if c < 8 { 0 } else { c as u8 });
+        lut[15] = 200;
+        let mut tile = original.clone();
+        tile.remap_colors(&lut);
+        for y in 0..8 {
+            for x in 0..8 {
+                let c = original.get_pixel(x, y);
+                assert_eq!(tile.get_pixel(x, y), if c < 8 { 0 } else { c });
+            }
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_tile_to_image_buffer() {
+        let mut palette = Palette::new();
+        palette.set_color(4, 7, Color::new(31, 16, 0));
+        let mut tile = Tile::new();
+        tile.set_pixel(2, 5, 7);
+
+        let image = tile.to_image_buffer(&palette, 4);
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(image.get_pixel(2, 5).0, [255, 132, 0, 255]);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_tile_2bpp_round_trip() {
+        let mut tile = Tile::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                tile.set_pixel(x, y, ((x + y * 3) % 4) as u8);
+            }
+        }
+        assert_eq!(Tile::from_2bpp(&tile.to_2bpp()), tile);
+
+        let mut tile = Tile::new();
+        tile.set_pixel(0, 0, 3);
+        tile.set_pixel(7, 1, 2);
+        let data = tile.to_2bpp();
+        assert_eq!(&data[0..4], &[0x80, 0x80, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn test_tile_2bpp_is_lossy_above_three() {
+        let mut tile = Tile::new();
+        tile.set_pixel(0, 0, 6); // 0b0110 keeps 0b10
+        tile.set_pixel(1, 0, 13); // 0b1101 keeps 0b01
+        tile.set_pixel(2, 0, 12); // 0b1100 keeps 0b00
+
+        let decoded = Tile::from_2bpp(&tile.to_2bpp());
+        assert_eq!(decoded.get_pixel(0, 0), 2);
+        assert_eq!(decoded.get_pixel(1, 0), 1);
+        assert_eq!(decoded.get_pixel(2, 0), 0);
+        assert_ne!(decoded, tile);
+    }
+
+    #[test]
+    fn test_tile_8bpp_round_trip_and_clamp() {
+        let tile = Tile::pseudo_random(9);
+        let data = tile.to_8bpp();
+        assert_eq!(data[3 * 8 + 5], tile.get_pixel(5, 3));
+        assert_eq!(Tile::from_8bpp(&data), tile);
+
+        let mut data = [0u8; 64];
+        data[10] = 200;
+        assert_eq!(Tile::from_8bpp(&data).get_pixel(2, 1), 15);
+    }
+
+    #[test]
+    fn test_pack_planar_row() {
+        let row = [0, 1, 2, 3, 4, 8, 15, 9];
+        // Plane 0 (bit 0): pixels 1, 3, 6, 7
+        // Plane 1 (bit 1): pixels 2, 3, 6
+        // Plane 2 (bit 2): pixels 4, 6
+        // Plane 3 (bit 3): pixels 5, 6, 7
+        let planes = pack_planar_row(&row);
+        assert_eq!(planes, [0b0101_0011, 0b0011_0010, 0b0000_1010, 0b0000_0111]);
+        assert_eq!(unpack_planar_row(&planes), row);
+
+        // Matches the corresponding bytes of a full tile's planar data
+        let mut tile = Tile::new();
+        for (x, &color) in row.iter().enumerate() {
+            tile.set_pixel(x, 2, color);
+        }
+        let planar = tile.to_planar();
+        assert_eq!([planar[2], planar[10], planar[18], planar[26]], planes);
+    }
+
+    #[test]
+    fn test_tile_packed_4bpp_nibble_order() {
+        let mut tile = Tile::new();
+        for (x, color) in [1, 2, 3, 4, 0xA, 0xB, 0xC, 0xF].into_iter().enumerate() {
+            tile.set_pixel(x, 1, color);
+        }
+
+        let packed = tile.to_packed_4bpp();
+        assert_eq!(&packed[4..8], &[0x12, 0x34, 0xAB, 0xCF]);
+        assert_eq!(&packed[0..4], &[0; 4]);
+        assert_eq!(Tile::from_packed_4bpp(&packed), tile);
+    }
+
+    #[test]
+    fn test_tile_from_indices() {
+        let tile = Tile::pseudo_random(13);
+        let indices = tile.to_indices();
+        assert_eq!(Tile::from_indices(&indices), Some(tile));
+
+        assert_eq!(Tile::from_indices(&indices[..63]), None);
+        assert_eq!(Tile::from_indices(&[0; 65]), None);
+        assert_eq!(Tile::from_indices(&[99; 64]).unwrap().get_pixel(7, 7), 15);
+    }
+
+    #[test]
+    fn test_tile_hash_matches_equality() {
+        use std::collections::hash_map::DefaultHasher;
+        let hash = |tile: &Tile| {
+            let mut hasher = DefaultHasher::new();
+            tile.hash(&mut hasher);
+            hasher.finish()
+        };
+        let tile = Tile::pseudo_random(3);
+        assert_eq!(hash(&tile), hash(&tile.clone()));
+        assert_ne!(hash(&tile), hash(&Tile::pseudo_random(4)));
+    }
+
+    #[test]
+    fn test_row_roundtrip() {
+        let mut tile = Tile::new();
+        tile.set_row(3, &[0, 1, 2, 3, 4, 5, 6, 20]);
+        assert_eq!(tile.row(3), Some([0, 1, 2, 3, 4, 5, 6, 15]));
+        assert_eq!(tile.get_pixel(6, 3), 6);
+        assert_eq!(tile.row(2), Some([0; 8]));
+    }
+
+    #[test]
+    fn test_row_out_of_range_is_ignored() {
+        let mut tile = Tile::pseudo_random(5);
+        let before = tile.clone();
+        tile.set_row(8, &[1; 8]);
+        assert_eq!(tile, before);
+        assert_eq!(tile.row(8), None);
+    }
+
+    #[test]
+    fn test_column_roundtrip() {
+        let mut tile = Tile::new();
+        tile.set_column(6, &[9, 8, 7, 6, 5, 4, 3, 99]);
+        assert_eq!(tile.column(6), Some([9, 8, 7, 6, 5, 4, 3, 15]));
+        assert_eq!(tile.get_pixel(6, 1), 8);
+        assert_eq!(tile.row(0), Some([0, 0, 0, 0, 0, 0, 9, 0]));
+        assert_eq!(tile.column(8), None);
+        tile.set_column(8, &[1; 8]);
+        assert_eq!(tile.column(6), Some([9, 8, 7, 6, 5, 4, 3, 15]));
+    }
+
+    #[test]
+    fn test_blit_full_tile() {
+        let src = Tile::pseudo_random(11);
+        let mut dst = Tile::new();
+        dst.blit(&src, 0, 0, 8, 8, 0, 0);
+        assert_eq!(dst, src);
+    }
+
+    #[test]
+    fn test_blit_clips_at_edges() {
+        let src = Tile::pseudo_random(12);
+        let mut dst = Tile::from_flat(&[15; 64]);
+        dst.blit(&src, 5, 0, 4, 3, 6, 6);
+        for y in 0..8 {
+            for x in 0..8 {
+                let expected = if x >= 6 && y >= 6 {
+                    src.get_pixel(x - 1, y - 6)
+                } else {
+                    15
+                };
+                assert_eq!(dst.get_pixel(x, y), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_blit_zero_size_is_noop() {
+        let src = Tile::pseudo_random(13);
+        let mut dst = Tile::pseudo_random(14);
+        let before = dst.clone();
+        dst.blit(&src, 0, 0, 0, 8, 0, 0);
+        dst.blit(&src, 0, 0, 8, 0, 0, 0);
+        dst.blit(&src, 8, 0, 8, 8, 0, 0);
+        assert_eq!(dst, before);
+    }
+
+    #[test]
+    fn test_planar_from_into_matches_named_methods() {
+        let tile = Tile::pseudo_random(21);
+        let planar: [u8; 32] = (&tile).into();
+        assert_eq!(planar, tile.to_planar());
+        let decoded: Tile = planar.into();
+        assert_eq!(decoded, Tile::from_planar(&planar));
+        assert_eq!(decoded, tile);
+    }
+
+    #[test]
+    fn test_shift_right_wraps_last_column() {
+        let original = Tile::pseudo_random(31);
+        let mut tile = original.clone();
+        tile.shift(1, 0);
+        for y in 0..8 {
+            assert_eq!(tile.get_pixel(0, y), original.get_pixel(7, y));
+            for x in 1..8 {
+                assert_eq!(tile.get_pixel(x, y), original.get_pixel(x - 1, y));
+            }
+        }
+        tile.shift(-1, 0);
+        assert_eq!(tile, original);
+    }
+
+    #[test]
+    fn test_shift_negative_vertical() {
+        let original = Tile::pseudo_random(32);
+        let mut tile = original.clone();
+        tile.shift(0, -3);
+        for y in 0..8 {
+            assert_eq!(tile.row(y), original.row((y + 3) % 8));
+        }
+        tile.shift(8, 11);
+        assert_eq!(tile, original);
+    }
+
+    #[test]
+    fn test_pixels_iterator() {
+        let mut tile = Tile::new();
+        tile.set_pixel(3, 0, 4);
+        tile.set_pixel(7, 7, 15);
+        let pixels: Vec<(usize, usize, u8)> = tile.pixels().collect();
+        assert_eq!(pixels.len(), 64);
+        assert_eq!(pixels[0], (0, 0, 0));
+        assert_eq!(pixels[3], (3, 0, 4));
+        assert_eq!(pixels[9], (1, 1, 0));
+        assert_eq!(pixels[63], (7, 7, 15));
+        assert_eq!(tile.pixels().filter(|&(_, _, color)| color != 0).count(), 2);
+    }
+
+    #[test]
+    fn test_canonical_is_shared_by_flip_variants() {
+        let tile = Tile::pseudo_random(40);
+        let (canonical, _, _) = tile.canonical();
+        for (variant, _, _) in tile.flip_variants() {
+            let (variant_canonical, h_flip, v_flip) = variant.canonical();
+            assert_eq!(variant_canonical, canonical);
+            assert_eq!(variant_canonical.flipped(h_flip, v_flip), variant);
+        }
+    }
+
+    #[test]
+    fn test_canonical_of_symmetric_tile_has_no_flips() {
+        let tile = Tile::from_flat(&[3; 64]);
+        assert_eq!(tile.canonical(), (tile.clone(), false, false));
     }
 }
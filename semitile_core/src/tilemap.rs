@@ -15,6 +15,15 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::error::SemitileError;
+use crate::palette::{Color, Palette};
+use crate::rng::SplitMix64;
+use crate::tile::Tile;
+
 /// Represents a tilemap entry (16-bit value)
 ///
 /// Format (Cicada-16 Hardware Spec):
@@ -25,7 +34,14 @@
 /// - Bit 9: Tile index bit 9
 /// - Bit 8: Tile index bit 8
 /// - Bits 0-7: Tile index bits 0-7
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///
+/// With the `serde` feature, serializes as this 16-bit value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "u16", into = "u16")
+)]
 pub struct TilemapEntry {
     tile_index: u16, // 0-1023 (10 bits)
     h_flip: bool,
@@ -88,6 +104,26 @@ impl TilemapEntry {
         }
     }
 
+    /// Creates a tilemap entry from its 16-bit value in little-endian byte order
+    pub fn from_le_bytes(bytes: [u8; 2]) -> Self {
+        Self::from_u16(u16::from_le_bytes(bytes))
+    }
+
+    /// Creates a tilemap entry from its 16-bit value in big-endian byte order
+    pub fn from_be_bytes(bytes: [u8; 2]) -> Self {
+        Self::from_u16(u16::from_be_bytes(bytes))
+    }
+
+    /// Returns the 16-bit value as little-endian bytes, as used by `Tilemap::export_binary()`
+    pub fn to_le_bytes(&self) -> [u8; 2] {
+        self.to_u16().to_le_bytes()
+    }
+
+    /// Returns the 16-bit value as big-endian bytes
+    pub fn to_be_bytes(&self) -> [u8; 2] {
+        self.to_u16().to_be_bytes()
+    }
+
     /// Returns the tile index (0-1023)
     pub fn tile_index(&self) -> u16 {
         self.tile_index
@@ -113,11 +149,37 @@ impl TilemapEntry {
         self.priority
     }
 
+    /// Compares two entries, treating the priority flag as a don't-care
+    pub fn eq_ignoring_priority(&self, other: &TilemapEntry) -> bool {
+        (self.to_u16() ^ other.to_u16()) & !(1 << 15) == 0
+    }
+
+    /// Compares two entries, treating both flip flags as don't-cares
+    pub fn eq_ignoring_flips(&self, other: &TilemapEntry) -> bool {
+        (self.to_u16() ^ other.to_u16()) & !(0b11 << 13) == 0
+    }
+
     /// Sets the tile index (will be clamped to 0-1023)
     pub fn set_tile_index(&mut self, tile_index: u16) {
         self.tile_index = tile_index.min(1023);
     }
 
+    /// Sets the tile index, rejecting values above 1023 instead of clamping
+    ///
+    /// Returns `SemitileError::OutOfBounds` and leaves the entry unchanged if
+    /// `tile_index` > 1023
+    pub fn set_tile_index_checked(&mut self, tile_index: u16) -> Result<(), SemitileError> {
+        if tile_index > 1023 {
+            return Err(SemitileError::OutOfBounds {
+                index: tile_index as usize,
+                min: 0,
+                max: 1023,
+            });
+        }
+        self.tile_index = tile_index;
+        Ok(())
+    }
+
     /// Sets the palette index (will be clamped to 0-7)
     pub fn set_palette_idx(&mut self, palette_idx: u8) {
         self.palette_idx = palette_idx.min(7);
@@ -139,26 +201,193 @@ impl TilemapEntry {
     }
 }
 
-impl Default for TilemapEntry {
-    fn default() -> Self {
+impl From<u16> for TilemapEntry {
+    /// Same as `TilemapEntry::from_u16()`
+    fn from(value: u16) -> Self {
+        Self::from_u16(value)
+    }
+}
+
+impl From<TilemapEntry> for u16 {
+    /// Same as `TilemapEntry::to_u16()`
+    fn from(entry: TilemapEntry) -> Self {
+        entry.to_u16()
+    }
+}
+
+/// Reason a tilemap entry was flagged by `Tilemap::suspicious_entries()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SuspiciousReason {
+    /// Tile 0 (usually blank) with the priority flag set
+    BlankTileWithPriority,
+    /// Tile 0 (usually blank) with a non-zero palette index
+    BlankTileWithPalette,
+    /// Tile 0 (usually blank) with a horizontal or vertical flip
+    BlankTileWithFlip,
+}
+
+/// Entry counts by flip combination, returned by `Tilemap::flip_stats()`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlipStats {
+    /// Entries with no flips
+    pub none: usize,
+    /// Entries with only the horizontal flip set
+    pub h_only: usize,
+    /// Entries with only the vertical flip set
+    pub v_only: usize,
+    /// Entries with both flips set
+    pub both: usize,
+}
+
+/// Kind of modification reported to a `Tilemap::on_change()` callback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// A single entry was written by `set_entry()`, `flood_fill_where()`,
+    /// `map_entries()`, `replace_tile()` or `assign_palettes_by_coverage()`
+    EntrySet { x: usize, y: usize },
+    /// The tilemap was resized to new dimensions
+    Resized { width: usize, height: usize },
+    /// Every entry was overwritten via `fill()`
+    Filled,
+    /// Every entry was reset via `clear()`
+    Cleared,
+}
+
+/// Binary layout written by `Tilemap::save()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaveFormat {
+    /// Headerless entries, identical to `export_binary()`
+    Raw,
+    /// Header followed by run-length encoded entries
+    Rle,
+    /// Header followed by raw entries
+    RawVersioned,
+}
+
+/// Magic bytes that start a headered tilemap file
+const SAVE_MAGIC: &[u8; 4] = b"STMP";
+
+/// Current version of the headered tilemap format
+const SAVE_VERSION: u8 = 1;
+
+/// Header size: magic, version, format, width (u16), height (u16)
+const SAVE_HEADER_SIZE: usize = 10;
+
+/// Format byte for raw entries after the header
+const SAVE_FORMAT_RAW: u8 = 0;
+
+/// Format byte for run-length encoded entries after the header
+const SAVE_FORMAT_RLE: u8 = 1;
+
+/// Size in bytes of one `export_sparse()` record: x, y, entry (u16)
+const SPARSE_RECORD_SIZE: usize = 4;
+
+/// Serialized form of a `Tilemap`, validated on deserialization
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TilemapData {
+    width: usize,
+    height: usize,
+    entries: Vec<TilemapEntry>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Tilemap> for TilemapData {
+    fn from(tilemap: Tilemap) -> Self {
         Self {
-            tile_index: 0,
-            h_flip: false,
-            v_flip: false,
-            priority: false,
-            palette_idx: 0,
+            width: tilemap.width,
+            height: tilemap.height,
+            entries: tilemap.entries,
         }
     }
 }
 
+#[cfg(feature = "serde")]
+impl TryFrom<TilemapData> for Tilemap {
+    type Error = SemitileError;
+
+    fn try_from(data: TilemapData) -> Result<Self, Self::Error> {
+        for size in [data.width, data.height] {
+            if !(1..=256).contains(&size) {
+                return Err(SemitileError::OutOfBounds {
+                    index: size,
+                    min: 1,
+                    max: 256,
+                });
+            }
+        }
+        if data.entries.len() != data.width * data.height {
+            return Err(SemitileError::InvalidLength {
+                expected: data.width * data.height,
+                actual: data.entries.len(),
+            });
+        }
+        Ok(Self {
+            width: data.width,
+            height: data.height,
+            entries: data.entries,
+            on_change: ChangeHook::default(),
+        })
+    }
+}
+
+/// Optional change callback held by a `Tilemap`
+///
+/// Not part of a tilemap's value: clones start without a callback and
+/// equality ignores it. The callback must be `Send + Sync` so that tilemaps
+/// stay `Send` and `Sync`.
+#[derive(Default)]
+struct ChangeHook(Option<Box<dyn FnMut(ChangeKind) + Send + Sync>>);
+
+impl ChangeHook {
+    fn notify(&mut self, kind: ChangeKind) {
+        if let Some(f) = &mut self.0 {
+            f(kind);
+        }
+    }
+}
+
+impl Clone for ChangeHook {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+impl fmt::Debug for ChangeHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() {
+            "Some(<callback>)"
+        } else {
+            "None"
+        })
+    }
+}
+
+impl PartialEq for ChangeHook {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ChangeHook {}
+
 /// Represents a tilemap with configurable dimensions
 ///
 /// Cicada-16 supports tilemaps up to 256×256 tiles (65536 entries)
+///
+/// With the `serde` feature, serializes as `width`, `height` and row-major
+/// `entries`; the change callback is not serialized
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "TilemapData", into = "TilemapData")
+)]
 pub struct Tilemap {
     width: usize,
     height: usize,
     entries: Vec<TilemapEntry>,
+    on_change: ChangeHook,
 }
 
 impl Tilemap {
@@ -178,7 +407,23 @@ impl Tilemap {
             width,
             height,
             entries,
+            on_change: ChangeHook::default(),
+        }
+    }
+
+    /// Creates a tilemap filled with deterministic pseudo-random entries
+    ///
+    /// # Arguments
+    /// * `width` - Width in tiles (1-256)
+    /// * `height` - Height in tiles (1-256)
+    /// * `seed` - Seed; the same seed and dimensions always produce the same map
+    pub fn pseudo_random(width: usize, height: usize, seed: u64) -> Self {
+        let mut rng = SplitMix64::new(seed);
+        let mut tilemap = Tilemap::new(width, height);
+        for entry in &mut tilemap.entries {
+            *entry = TilemapEntry::from_u16(rng.next_u64() as u16);
         }
+        tilemap
     }
 
     /// Returns the width of the tilemap in tiles
@@ -208,6 +453,7 @@ impl Tilemap {
     pub fn set_entry(&mut self, x: usize, y: usize, entry: TilemapEntry) {
         if x < self.width && y < self.height {
             self.entries[y * self.width + x] = entry;
+            self.on_change.notify(ChangeKind::EntrySet { x, y });
         }
     }
 
@@ -224,6 +470,34 @@ impl Tilemap {
         data
     }
 
+    /// Exports the tilemap with tile indices relative to a tile base offset
+    ///
+    /// # Arguments
+    /// * `base` - Tile base offset subtracted from every tile index
+    ///
+    /// Converts VRAM-relative tile indices into map-relative ones as addressed by
+    /// the hardware tile base register. Output format matches `export_binary()`.
+    /// Returns `SemitileError::OutOfBounds` if any entry's tile index is below `base`.
+    pub fn export_binary_with_base(&self, base: u16) -> Result<Vec<u8>, SemitileError> {
+        let mut data = Vec::with_capacity(self.entries.len() * 2);
+        for entry in &self.entries {
+            let index = entry
+                .tile_index()
+                .checked_sub(base)
+                .ok_or(SemitileError::OutOfBounds {
+                    index: entry.tile_index() as usize,
+                    min: base as usize,
+                    max: 1023,
+                })?;
+            let mut relative = *entry;
+            relative.set_tile_index(index);
+            let value = relative.to_u16();
+            data.push((value & 0xFF) as u8); // Low byte
+            data.push(((value >> 8) & 0xFF) as u8); // High byte
+        }
+        Ok(data)
+    }
+
     /// Imports a tilemap from binary data
     ///
     /// # Arguments
@@ -254,9 +528,185 @@ impl Tilemap {
             width,
             height,
             entries,
+            on_change: ChangeHook::default(),
         })
     }
 
+    /// Exports only the entries that differ from `TilemapEntry::default()`
+    ///
+    /// Each entry is written as a 4-byte record: x, y, then the entry's
+    /// 16-bit value (little-endian), in row-major order. Much smaller than
+    /// `export_binary()` for mostly empty maps; the dimensions are not stored.
+    pub fn export_sparse(&self) -> Vec<u8> {
+        let default = TilemapEntry::default();
+        let mut data = Vec::new();
+        for (i, entry) in self.entries.iter().enumerate() {
+            if *entry != default {
+                data.push((i % self.width) as u8);
+                data.push((i / self.width) as u8);
+                data.extend_from_slice(&entry.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    /// Imports a tilemap from `export_sparse()` data
+    ///
+    /// # Arguments
+    /// * `data` - Sparse records (a multiple of 4 bytes)
+    /// * `width` - Width in tiles (1-256)
+    /// * `height` - Height in tiles (1-256)
+    ///
+    /// Cells without a record keep the default entry. Returns
+    /// `SemitileError::InvalidLength` for a truncated record, or
+    /// `SemitileError::OutOfBounds` if a record lies outside the map.
+    pub fn import_sparse(data: &[u8], width: usize, height: usize) -> Result<Self, SemitileError> {
+        if !data.len().is_multiple_of(SPARSE_RECORD_SIZE) {
+            return Err(SemitileError::InvalidLength {
+                expected: data.len().next_multiple_of(SPARSE_RECORD_SIZE),
+                actual: data.len(),
+            });
+        }
+
+        let mut tilemap = Self::new(width, height);
+        for record in data.chunks_exact(SPARSE_RECORD_SIZE) {
+            let (x, y) = (record[0] as usize, record[1] as usize);
+            if x >= tilemap.width {
+                return Err(SemitileError::OutOfBounds {
+                    index: x,
+                    min: 0,
+                    max: tilemap.width - 1,
+                });
+            }
+            if y >= tilemap.height {
+                return Err(SemitileError::OutOfBounds {
+                    index: y,
+                    min: 0,
+                    max: tilemap.height - 1,
+                });
+            }
+            tilemap.entries[y * tilemap.width + x] =
+                TilemapEntry::from_le_bytes([record[2], record[3]]);
+        }
+        Ok(tilemap)
+    }
+
+    /// Serializes the tilemap in the given format
+    ///
+    /// `SaveFormat::Raw` is the headerless `export_binary()` layout. The other
+    /// formats start with a 10-byte header: magic `"STMP"`, version (1), format
+    /// byte (0 = raw, 1 = RLE), then width and height as little-endian u16.
+    /// RLE data is a sequence of 3-byte runs: a count (1-255) followed by the
+    /// repeated entry's 16-bit value (little-endian).
+    pub fn save(&self, format: SaveFormat) -> Vec<u8> {
+        let format_byte = match format {
+            SaveFormat::Raw => return self.export_binary(),
+            SaveFormat::Rle => SAVE_FORMAT_RLE,
+            SaveFormat::RawVersioned => SAVE_FORMAT_RAW,
+        };
+
+        let mut data = Vec::with_capacity(SAVE_HEADER_SIZE + self.entries.len() * 2);
+        data.extend_from_slice(SAVE_MAGIC);
+        data.push(SAVE_VERSION);
+        data.push(format_byte);
+        data.extend_from_slice(&(self.width as u16).to_le_bytes());
+        data.extend_from_slice(&(self.height as u16).to_le_bytes());
+
+        if format_byte == SAVE_FORMAT_RAW {
+            data.extend_from_slice(&self.export_binary());
+            return data;
+        }
+
+        let mut i = 0;
+        while i < self.entries.len() {
+            let entry = self.entries[i];
+            let run = self.entries[i..]
+                .iter()
+                .take(255)
+                .take_while(|&&e| e == entry)
+                .count();
+            data.push(run as u8);
+            data.extend_from_slice(&entry.to_le_bytes());
+            i += run;
+        }
+        data
+    }
+
+    /// Loads a tilemap saved with `SaveFormat::Rle` or `SaveFormat::RawVersioned`
+    ///
+    /// The decoder is picked from the header, so callers don't need to know
+    /// which format was used. Headerless `SaveFormat::Raw` data carries no
+    /// dimensions; load it with `import_binary()` instead.
+    ///
+    /// Returns `SemitileError::Parse` if the header is missing, has an
+    /// unsupported version or format, or the RLE runs don't cover the map
+    /// exactly. Returns `SemitileError::InvalidLength` if the data is truncated.
+    pub fn load(data: &[u8]) -> Result<Self, SemitileError> {
+        if data.len() < SAVE_HEADER_SIZE {
+            return Err(SemitileError::InvalidLength {
+                expected: SAVE_HEADER_SIZE,
+                actual: data.len(),
+            });
+        }
+        if &data[0..4] != SAVE_MAGIC {
+            return Err(SemitileError::Parse(
+                "missing tilemap header (use import_binary for raw data)".to_string(),
+            ));
+        }
+        if data[4] != SAVE_VERSION {
+            return Err(SemitileError::Parse(format!(
+                "unsupported tilemap version {}",
+                data[4]
+            )));
+        }
+        let width = u16::from_le_bytes([data[6], data[7]]) as usize;
+        let height = u16::from_le_bytes([data[8], data[9]]) as usize;
+        if !(1..=256).contains(&width) || !(1..=256).contains(&height) {
+            return Err(SemitileError::Parse(format!(
+                "invalid tilemap size {}×{}",
+                width, height
+            )));
+        }
+        let payload = &data[SAVE_HEADER_SIZE..];
+
+        match data[5] {
+            SAVE_FORMAT_RAW => {
+                Self::import_binary(payload, width, height).ok_or(SemitileError::InvalidLength {
+                    expected: SAVE_HEADER_SIZE + width * height * 2,
+                    actual: data.len(),
+                })
+            }
+            SAVE_FORMAT_RLE => {
+                if !payload.len().is_multiple_of(3) {
+                    return Err(SemitileError::InvalidLength {
+                        expected: SAVE_HEADER_SIZE + payload.len().next_multiple_of(3),
+                        actual: data.len(),
+                    });
+                }
+                let mut tilemap = Self::new(width, height);
+                tilemap.entries.clear();
+                for run in payload.chunks_exact(3) {
+                    let entry = TilemapEntry::from_le_bytes([run[1], run[2]]);
+                    tilemap
+                        .entries
+                        .extend(std::iter::repeat_n(entry, run[0] as usize));
+                }
+                if tilemap.entries.len() != width * height {
+                    return Err(SemitileError::Parse(format!(
+                        "RLE data decodes to {} entries, expected {}",
+                        tilemap.entries.len(),
+                        width * height
+                    )));
+                }
+                Ok(tilemap)
+            }
+            other => Err(SemitileError::Parse(format!(
+                "unknown tilemap format {}",
+                other
+            ))),
+        }
+    }
+
     /// Resizes the tilemap to new dimensions
     ///
     /// # Arguments
@@ -289,6 +739,10 @@ impl Tilemap {
         self.width = new_width;
         self.height = new_height;
         self.entries = new_entries;
+        self.on_change.notify(ChangeKind::Resized {
+            width: new_width,
+            height: new_height,
+        });
     }
 
     /// Clears the entire tilemap (sets all entries to default)
@@ -296,6 +750,7 @@ impl Tilemap {
         for entry in &mut self.entries {
             *entry = TilemapEntry::default();
         }
+        self.on_change.notify(ChangeKind::Cleared);
     }
 
     /// Fills the entire tilemap with a specific entry
@@ -303,6 +758,637 @@ impl Tilemap {
         for e in &mut self.entries {
             *e = entry;
         }
+        self.on_change.notify(ChangeKind::Filled);
+    }
+
+    /// Fills the entire tilemap with an entry built from its fields
+    ///
+    /// Shorthand for `fill(TilemapEntry::new(tile, palette, h_flip, v_flip, priority))`;
+    /// values are clamped the same way
+    pub fn fill_with_flips(
+        &mut self,
+        tile: u16,
+        palette: u8,
+        h_flip: bool,
+        v_flip: bool,
+        priority: bool,
+    ) {
+        self.fill(TilemapEntry::new(tile, palette, h_flip, v_flip, priority));
+    }
+
+    /// Flood-fills the region of matching entries around a cell
+    ///
+    /// # Arguments
+    /// * `x` - Start X coordinate
+    /// * `y` - Start Y coordinate
+    /// * `new` - Entry written to every filled cell
+    /// * `predicate` - Returns true for entries that belong to the region
+    ///
+    /// Every cell orthogonally connected to `(x, y)` whose entry satisfies
+    /// `predicate` is set to `new`; diagonal neighbors are not connected. Each
+    /// cell is visited once, so `new` may itself satisfy the predicate. Does
+    /// nothing if the coordinates are out of bounds or the start cell does
+    /// not match.
+    pub fn flood_fill_where(
+        &mut self,
+        x: usize,
+        y: usize,
+        new: TilemapEntry,
+        predicate: impl Fn(&TilemapEntry) -> bool,
+    ) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let mut visited = vec![false; self.entries.len()];
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            let index = y * self.width + x;
+            if visited[index] || !predicate(&self.entries[index]) {
+                continue;
+            }
+            visited[index] = true;
+            self.entries[index] = new;
+            self.on_change.notify(ChangeKind::EntrySet { x, y });
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if x + 1 < self.width {
+                stack.push((x + 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+            if y + 1 < self.height {
+                stack.push((x, y + 1));
+            }
+        }
+    }
+
+    /// Registers a callback invoked whenever the tilemap is modified
+    ///
+    /// The callback is called after `set_entry()` (in bounds only), `resize()`
+    /// (when the size actually changes), `fill()`, `fill_with_flips()` and
+    /// `clear()`. `flood_fill_where()` reports every cell it writes;
+    /// `map_entries()`, `replace_tile()` and `assign_palettes_by_coverage()`
+    /// report every cell whose entry changed. Registering a
+    /// new callback replaces the previous one. Tilemaps without a callback pay
+    /// no extra cost. Clones of a tilemap do not inherit the callback.
+    pub fn on_change(&mut self, f: Box<dyn FnMut(ChangeKind) + Send + Sync>) {
+        self.on_change = ChangeHook(Some(f));
+    }
+
+    /// Replaces every entry with the result of a closure
+    ///
+    /// The closure receives `(x, y, entry)` for each cell in row-major order
+    /// and returns the new entry for that cell
+    pub fn map_entries(&mut self, mut f: impl FnMut(usize, usize, TilemapEntry) -> TilemapEntry) {
+        for (i, entry) in self.entries.iter_mut().enumerate() {
+            let (x, y) = (i % self.width, i / self.width);
+            let new = f(x, y, *entry);
+            if new != *entry {
+                *entry = new;
+                self.on_change.notify(ChangeKind::EntrySet { x, y });
+            }
+        }
+    }
+
+    /// Rewrites every entry using tile `old` to use tile `new` instead
+    ///
+    /// # Arguments
+    /// * `old` - Tile index to replace
+    /// * `new` - Replacement tile index (will be clamped to 0-1023)
+    /// * `extra_h_flip` - Toggle the horizontal flip of rewritten entries
+    /// * `extra_v_flip` - Toggle the vertical flip of rewritten entries
+    ///
+    /// The flip flags are XORed so that replacing a tile with its mirror image
+    /// keeps each entry's on-screen appearance. Palette and priority are kept.
+    pub fn replace_tile(&mut self, old: u16, new: u16, extra_h_flip: bool, extra_v_flip: bool) {
+        for (i, entry) in self.entries.iter_mut().enumerate() {
+            if entry.tile_index() == old {
+                let before = *entry;
+                entry.set_tile_index(new);
+                entry.set_h_flip(entry.h_flip() ^ extra_h_flip);
+                entry.set_v_flip(entry.v_flip() ^ extra_v_flip);
+                if *entry != before {
+                    self.on_change.notify(ChangeKind::EntrySet {
+                        x: i % self.width,
+                        y: i / self.width,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Counts entries by flip combination
+    ///
+    /// Useful for judging whether flip-aware tile deduplication is worthwhile
+    pub fn flip_stats(&self) -> FlipStats {
+        let mut stats = FlipStats::default();
+        for entry in &self.entries {
+            match (entry.h_flip(), entry.v_flip()) {
+                (false, false) => stats.none += 1,
+                (true, false) => stats.h_only += 1,
+                (false, true) => stats.v_only += 1,
+                (true, true) => stats.both += 1,
+            }
+        }
+        stats
+    }
+
+    /// Returns true if any entry references the given tile index
+    ///
+    /// Stops scanning at the first match, so it is cheaper than building a
+    /// full usage count when only membership matters
+    pub fn contains_tile(&self, index: u16) -> bool {
+        Self::scan_for_tile(self.entries.iter(), index)
+    }
+
+    /// Consumes `entries` up to and including the first one using tile `index`
+    fn scan_for_tile<'a>(mut entries: impl Iterator<Item = &'a TilemapEntry>, index: u16) -> bool {
+        entries.any(|entry| entry.tile_index() == index)
+    }
+
+    /// Counts entries that differ from `TilemapEntry::default()`
+    ///
+    /// A quick emptiness metric, e.g. for deciding whether a map is worth saving
+    pub fn non_default_count(&self) -> usize {
+        let default = TilemapEntry::default();
+        self.entries
+            .iter()
+            .filter(|entry| **entry != default)
+            .count()
+    }
+
+    /// Returns true if both tilemaps have the same dimensions, tile indices and flips
+    ///
+    /// Palette indices and priority flags are ignored, so maps that share a
+    /// shape but differ only in coloring compare equal
+    pub fn same_layout(&self, other: &Tilemap) -> bool {
+        const LAYOUT_MASK: u16 = 0x3FF | (0b11 << 13); // Tile index and flips
+        self.width == other.width
+            && self.height == other.height
+            && self
+                .entries
+                .iter()
+                .zip(&other.entries)
+                .all(|(a, b)| (a.to_u16() ^ b.to_u16()) & LAYOUT_MASK == 0)
+    }
+
+    /// Builds a collision grid from a set of solid tile indices
+    ///
+    /// Returns a row-major `width * height` grid where `true` means walkable
+    /// and `false` means blocked (the cell's tile index is in `solid`)
+    pub fn walkability_grid(&self, solid: &HashSet<u16>) -> Vec<bool> {
+        self.entries
+            .iter()
+            .map(|entry| !solid.contains(&entry.tile_index()))
+            .collect()
+    }
+
+    /// Lints the tilemap for entries that are likely mistakes
+    ///
+    /// Tile 0 is conventionally blank, so attribute bits on it usually mean an
+    /// entry was left half-edited. Returns `(x, y, reason)` for each finding in
+    /// row-major order; an entry can be reported for several reasons.
+    pub fn suspicious_entries(&self) -> Vec<(usize, usize, SuspiciousReason)> {
+        let mut findings = Vec::new();
+        for (i, entry) in self.entries.iter().enumerate() {
+            if entry.tile_index() != 0 {
+                continue;
+            }
+            let (x, y) = (i % self.width, i / self.width);
+            if entry.priority() {
+                findings.push((x, y, SuspiciousReason::BlankTileWithPriority));
+            }
+            if entry.palette_idx() != 0 {
+                findings.push((x, y, SuspiciousReason::BlankTileWithPalette));
+            }
+            if entry.h_flip() || entry.v_flip() {
+                findings.push((x, y, SuspiciousReason::BlankTileWithFlip));
+            }
+        }
+        findings
+    }
+
+    /// Renders the whole tilemap to RGBA8888 pixel data
+    ///
+    /// # Arguments
+    /// * `tiles` - Tile data indexed by each entry's tile index
+    /// * `palette` - Palette to look colors up in
+    ///
+    /// Returns a `(width * 8)×(height * 8)` row-major buffer. Each entry's tile
+    /// is drawn with its flips and sub-palette; entries referencing a tile
+    /// index outside `tiles` are drawn as a blank tile.
+    pub fn render_rgba(&self, tiles: &[Tile], palette: &Palette) -> Vec<u8> {
+        let row_bytes = self.width * 8 * 4;
+        let mut rgba = vec![0u8; row_bytes * self.height * 8];
+        let blank = Tile::new();
+        for (i, entry) in self.entries.iter().enumerate() {
+            let (x, y) = (i % self.width, i / self.width);
+            let tile = tiles.get(entry.tile_index() as usize).unwrap_or(&blank);
+            let pixels = tile
+                .flipped(entry.h_flip(), entry.v_flip())
+                .render_rgba(palette, entry.palette_idx());
+            for (row, src) in pixels.chunks_exact(32).enumerate() {
+                let offset = (y * 8 + row) * row_bytes + x * 32;
+                rgba[offset..offset + 32].copy_from_slice(src);
+            }
+        }
+        rgba
+    }
+
+    /// Renders a single scanline to RGBA8888, as an emulator's PPU would
+    ///
+    /// # Arguments
+    /// * `tiles` - Tile data indexed by each entry's tile index
+    /// * `palette` - Palette to look colors up in
+    /// * `scanline` - Pixel row to render (wraps at `height * 8`)
+    /// * `scroll_x` - Horizontal scroll in pixels (wraps at `width * 8`)
+    ///
+    /// Returns `width * 8` pixels (4 bytes each). Screen column `sx` shows map
+    /// column `(sx + scroll_x) % (width * 8)`. Flips and sub-palettes are
+    /// honored and missing tiles are drawn blank, matching `render_rgba()`.
+    pub fn render_scanline_rgba(
+        &self,
+        tiles: &[Tile],
+        palette: &Palette,
+        scanline: usize,
+        scroll_x: usize,
+    ) -> Vec<u8> {
+        let map_width = self.width * 8;
+        let y = scanline % (self.height * 8);
+        let blank = Tile::new();
+        let mut rgba = Vec::with_capacity(map_width * 4);
+        for sx in 0..map_width {
+            let x = (sx + scroll_x) % map_width;
+            let entry = self.entries[(y / 8) * self.width + x / 8];
+            let tile = tiles.get(entry.tile_index() as usize).unwrap_or(&blank);
+            let tx = if entry.h_flip() { 7 - x % 8 } else { x % 8 };
+            let ty = if entry.v_flip() { 7 - y % 8 } else { y % 8 };
+            let color = palette.get_color(entry.palette_idx(), tile.get_pixel(tx, ty));
+            let (r, g, b) = color.to_rgb888();
+            rgba.extend_from_slice(&[r, g, b, 255]);
+        }
+        rgba
+    }
+
+    /// Assigns every entry the background sub-palette (0-7) that best covers
+    /// its tile's colors
+    ///
+    /// # Arguments
+    /// * `tiles` - Tile data indexed by each entry's tile index
+    /// * `palette` - Palette whose first 8 sub-palettes are candidates
+    ///
+    /// This is a coverage heuristic, not a color error metric: it only checks
+    /// which slots a sub-palette defines, never how close its colors are to
+    /// anything. A sub-palette's slots after its last non-black color are
+    /// considered unset; black slots before that are deliberate colors. The
+    /// cost for a tile is the number of non-transparent pixels (color index
+    /// != 0) that fall on unset slots. Each entry gets the sub-palette with the
+    /// lowest cost; sub-palettes that cover all of a tile's colors tie, and on
+    /// ties the current palette index is kept if it is among the best,
+    /// otherwise the lowest index wins. Entries referencing a tile index
+    /// outside `tiles` are left unchanged.
+    pub fn assign_palettes_by_coverage(&mut self, tiles: &[Tile], palette: &Palette) {
+        let placeholder = Color::default();
+        let set_slots: Vec<u8> = (0..8)
+            .map(|sub| {
+                (0..16u8)
+                    .rev()
+                    .find(|&index| palette.get_color(sub, index) != placeholder)
+                    .map_or(0, |last| last + 1)
+            })
+            .collect();
+        for (i, entry) in self.entries.iter_mut().enumerate() {
+            let Some(tile) = tiles.get(entry.tile_index() as usize) else {
+                continue;
+            };
+            let pixels = tile.to_flat();
+            let unset_pixels = |sub: u8| {
+                pixels
+                    .iter()
+                    .filter(|&&index| index != 0 && index >= set_slots[sub as usize])
+                    .count()
+            };
+            let current = entry.palette_idx();
+            let best = (0..8)
+                .min_by_key(|&sub| (unset_pixels(sub), sub != current, sub))
+                .unwrap_or(current);
+            if best != current {
+                entry.set_palette_idx(best);
+                self.on_change.notify(ChangeKind::EntrySet {
+                    x: i % self.width,
+                    y: i / self.width,
+                });
+            }
+        }
+    }
+
+    /// Counts how often each palette color is drawn across the whole tilemap
+    ///
+    /// # Arguments
+    /// * `tiles` - Tile data indexed by each entry's tile index
+    ///
+    /// Returns a `[sub_palette][color_idx]` table of pixel counts. Each entry
+    /// contributes its tile's 64 pixels to the sub-palette it selects. Entries
+    /// referencing a tile index outside `tiles` are skipped.
+    pub fn color_frequency(&self, tiles: &[Tile]) -> [[u32; 16]; 16] {
+        let mut counts = [[0u32; 16]; 16];
+        for entry in &self.entries {
+            if let Some(tile) = tiles.get(entry.tile_index() as usize) {
+                let sub = entry.palette_idx() as usize;
+                for y in 0..8 {
+                    for x in 0..8 {
+                        counts[sub][tile.get_pixel(x, y) as usize] += 1;
+                    }
+                }
+            }
+        }
+        counts
+    }
+}
+
+impl<'a> IntoIterator for &'a Tilemap {
+    type Item = &'a TilemapEntry;
+    type IntoIter = std::slice::Iter<'a, TilemapEntry>;
+
+    /// Iterates over entries in row-major order
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// Width and height of a single `ChunkedTilemap` chunk in tiles
+pub const CHUNK_SIZE: usize = 256;
+
+/// Represents a logical tilemap larger than the hardware limit
+///
+/// The map is stored as a grid of `Tilemap` chunks of up to 256×256 tiles each,
+/// so every chunk can still be exported in the hardware format. Chunks along
+/// the right and bottom edges are sized to the remaining width/height.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkedTilemap {
+    width: usize,
+    height: usize,
+    chunks_x: usize,
+    chunks_y: usize,
+    chunks: Vec<Tilemap>,
+}
+
+impl ChunkedTilemap {
+    /// Creates a new chunked tilemap with the specified global dimensions
+    ///
+    /// # Arguments
+    /// * `width` - Width in tiles (minimum 1)
+    /// * `height` - Height in tiles (minimum 1)
+    ///
+    /// All entries are initialized to default (tile 0, palette 0, no flips)
+    pub fn new(width: usize, height: usize) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        let chunks_x = width.div_ceil(CHUNK_SIZE);
+        let chunks_y = height.div_ceil(CHUNK_SIZE);
+
+        let mut chunks = Vec::with_capacity(chunks_x * chunks_y);
+        for cy in 0..chunks_y {
+            for cx in 0..chunks_x {
+                let chunk_width = (width - cx * CHUNK_SIZE).min(CHUNK_SIZE);
+                let chunk_height = (height - cy * CHUNK_SIZE).min(CHUNK_SIZE);
+                chunks.push(Tilemap::new(chunk_width, chunk_height));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            chunks_x,
+            chunks_y,
+            chunks,
+        }
+    }
+
+    /// Returns the global width in tiles
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the global height in tiles
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the number of chunk columns
+    pub fn chunks_x(&self) -> usize {
+        self.chunks_x
+    }
+
+    /// Returns the number of chunk rows
+    pub fn chunks_y(&self) -> usize {
+        self.chunks_y
+    }
+
+    /// Gets the entry at global coordinates
+    ///
+    /// Returns None if coordinates are out of bounds
+    pub fn get_entry(&self, gx: usize, gy: usize) -> Option<TilemapEntry> {
+        if gx >= self.width || gy >= self.height {
+            return None;
+        }
+        self.chunk(gx / CHUNK_SIZE, gy / CHUNK_SIZE)?
+            .get_entry(gx % CHUNK_SIZE, gy % CHUNK_SIZE)
+    }
+
+    /// Sets the entry at global coordinates
+    ///
+    /// Does nothing if coordinates are out of bounds
+    pub fn set_entry(&mut self, gx: usize, gy: usize, entry: TilemapEntry) {
+        if gx >= self.width || gy >= self.height {
+            return;
+        }
+        if let Some(mut chunk) = self.chunk_mut(gx / CHUNK_SIZE, gy / CHUNK_SIZE) {
+            chunk.set_entry(gx % CHUNK_SIZE, gy % CHUNK_SIZE, entry);
+        }
+    }
+
+    /// Returns the chunk at chunk coordinates, or None if out of bounds
+    pub fn chunk(&self, cx: usize, cy: usize) -> Option<&Tilemap> {
+        if cx < self.chunks_x && cy < self.chunks_y {
+            Some(&self.chunks[cy * self.chunks_x + cx])
+        } else {
+            None
+        }
+    }
+
+    /// Returns a write view of the chunk at chunk coordinates
+    ///
+    /// The view can only change entries, never a chunk's dimensions, so the
+    /// chunk grid always matches `width()` and `height()`. Writes through it
+    /// are reported to the callback set with `on_change()`. Returns None if
+    /// the chunk coordinates are out of bounds.
+    pub fn chunk_mut(&mut self, cx: usize, cy: usize) -> Option<ChunkMut<'_>> {
+        if cx < self.chunks_x && cy < self.chunks_y {
+            Some(ChunkMut(&mut self.chunks[cy * self.chunks_x + cx]))
+        } else {
+            None
+        }
+    }
+
+    /// Registers a callback invoked whenever any chunk is modified
+    ///
+    /// The callback receives the chunk coordinates `(cx, cy)` and the change
+    /// as reported by that chunk's `Tilemap::on_change()`, so `EntrySet`
+    /// coordinates are local to the chunk. Registering a new callback replaces
+    /// the previous one.
+    pub fn on_change(&mut self, f: Box<dyn FnMut(usize, usize, ChangeKind) + Send + Sync>) {
+        let shared = Arc::new(Mutex::new(f));
+        for (i, chunk) in self.chunks.iter_mut().enumerate() {
+            let (cx, cy) = (i % self.chunks_x, i / self.chunks_x);
+            let shared = Arc::clone(&shared);
+            chunk.on_change(Box::new(move |kind| {
+                if let Ok(mut f) = shared.lock() {
+                    f(cx, cy, kind);
+                }
+            }));
+        }
+    }
+
+    /// Exports a single chunk as hardware binary data
+    ///
+    /// See `Tilemap::export_binary()` for the format. Returns None if the
+    /// chunk coordinates are out of bounds.
+    pub fn export_chunk_binary(&self, cx: usize, cy: usize) -> Option<Vec<u8>> {
+        self.chunk(cx, cy).map(Tilemap::export_binary)
+    }
+}
+
+/// Write access to one chunk of a `ChunkedTilemap`
+///
+/// Dereferences to the chunk's `Tilemap` for reading and exporting. Only
+/// writes that keep the chunk's dimensions are exposed; resizing a chunk
+/// would desynchronize it from the chunk grid.
+#[derive(Debug)]
+pub struct ChunkMut<'a>(&'a mut Tilemap);
+
+impl ChunkMut<'_> {
+    /// Sets an entry at chunk-local coordinates
+    ///
+    /// Does nothing if coordinates are out of bounds
+    pub fn set_entry(&mut self, x: usize, y: usize, entry: TilemapEntry) {
+        self.0.set_entry(x, y, entry);
+    }
+
+    /// Fills the entire chunk with a specific entry
+    pub fn fill(&mut self, entry: TilemapEntry) {
+        self.0.fill(entry);
+    }
+
+    /// Clears the entire chunk (sets all entries to default)
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+impl std::ops::Deref for ChunkMut<'_> {
+    type Target = Tilemap;
+
+    fn deref(&self) -> &Tilemap {
+        self.0
+    }
+}
+
+/// Size in bytes of one serialized `TilemapEditLog` edit
+const EDIT_RECORD_SIZE: usize = 10;
+
+/// Ordered record of tilemap edits that can be serialized and replayed
+///
+/// Each edit is `(timestamp_index, x, y, entry)`. Logs let clients sync edit
+/// history by exchanging serialized edits instead of whole maps.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TilemapEditLog {
+    edits: Vec<(u32, usize, usize, TilemapEntry)>,
+}
+
+impl TilemapEditLog {
+    /// Creates a new empty edit log
+    pub fn new() -> Self {
+        Self { edits: Vec::new() }
+    }
+
+    /// Returns the number of recorded edits
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Returns true if no edits have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Returns the recorded edits in recording order
+    pub fn edits(&self) -> &[(u32, usize, usize, TilemapEntry)] {
+        &self.edits
+    }
+
+    /// Records that `entry` was written at `(x, y)` at `timestamp_index`
+    ///
+    /// Coordinates are stored unchanged; edits outside a map are skipped when
+    /// replayed
+    pub fn record(&mut self, timestamp_index: u32, x: usize, y: usize, entry: TilemapEntry) {
+        self.edits.push((timestamp_index, x, y, entry));
+    }
+
+    /// Applies every edit to `map` in timestamp order
+    ///
+    /// Edits with equal timestamps are applied in recording order. Edits
+    /// outside the map's bounds are ignored, as with `Tilemap::set_entry()`.
+    pub fn replay_onto(&self, map: &mut Tilemap) {
+        let mut ordered: Vec<_> = self.edits.iter().collect();
+        ordered.sort_by_key(|(timestamp_index, _, _, _)| *timestamp_index);
+        for &&(_, x, y, entry) in &ordered {
+            map.set_entry(x, y, entry);
+        }
+    }
+
+    /// Serializes the log to binary (10 bytes per edit, little-endian)
+    ///
+    /// Each edit is stored as the timestamp index (u32), x (u16), y (u16) and
+    /// the entry's 16-bit value (see `TilemapEntry::to_u16()`). Coordinates
+    /// above 65535 are written as 65535, which is still outside every tilemap.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.edits.len() * EDIT_RECORD_SIZE);
+        for &(timestamp_index, x, y, entry) in &self.edits {
+            let x = x.min(u16::MAX as usize) as u16;
+            let y = y.min(u16::MAX as usize) as u16;
+            data.extend_from_slice(&timestamp_index.to_le_bytes());
+            data.extend_from_slice(&x.to_le_bytes());
+            data.extend_from_slice(&y.to_le_bytes());
+            data.extend_from_slice(&entry.to_le_bytes());
+        }
+        data
+    }
+
+    /// Deserializes a log produced by `serialize()`
+    ///
+    /// Returns `SemitileError::InvalidLength` if `data` is not a whole number
+    /// of 10-byte edits
+    pub fn deserialize(data: &[u8]) -> Result<Self, SemitileError> {
+        if !data.len().is_multiple_of(EDIT_RECORD_SIZE) {
+            return Err(SemitileError::InvalidLength {
+                expected: data.len().next_multiple_of(EDIT_RECORD_SIZE),
+                actual: data.len(),
+            });
+        }
+
+        let mut log = Self::new();
+        for record in data.chunks_exact(EDIT_RECORD_SIZE) {
+            let timestamp_index = u32::from_le_bytes([record[0], record[1], record[2], record[3]]);
+            let x = u16::from_le_bytes([record[4], record[5]]) as usize;
+            let y = u16::from_le_bytes([record[6], record[7]]) as usize;
+            let entry = TilemapEntry::from_le_bytes([record[8], record[9]]);
+            log.record(timestamp_index, x, y, entry);
+        }
+        Ok(log)
     }
 }
 
@@ -315,9 +1401,9 @@ mod tests {
         let entry = TilemapEntry::new(100, 5, true, false, false);
         assert_eq!(entry.tile_index(), 100);
         assert_eq!(entry.palette_idx(), 5);
-        assert_eq!(entry.h_flip(), true);
-        assert_eq!(entry.v_flip(), false);
-        assert_eq!(entry.priority(), false);
+        assert!(entry.h_flip());
+        assert!(!entry.v_flip());
+        assert!(!entry.priority());
     }
 
     #[test]
@@ -359,9 +1445,9 @@ mod tests {
         let entry = TilemapEntry::default();
         assert_eq!(entry.tile_index(), 0);
         assert_eq!(entry.palette_idx(), 0);
-        assert_eq!(entry.h_flip(), false);
-        assert_eq!(entry.v_flip(), false);
-        assert_eq!(entry.priority(), false);
+        assert!(!entry.h_flip());
+        assert!(!entry.v_flip());
+        assert!(!entry.priority());
     }
 
     #[test]
@@ -376,9 +1462,9 @@ mod tests {
 
         assert_eq!(entry.tile_index(), 456);
         assert_eq!(entry.palette_idx(), 6);
-        assert_eq!(entry.h_flip(), true);
-        assert_eq!(entry.v_flip(), true);
-        assert_eq!(entry.priority(), true);
+        assert!(entry.h_flip());
+        assert!(entry.v_flip());
+        assert!(entry.priority());
     }
 
     #[test]
@@ -562,4 +1648,839 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_tilemap_color_frequency() {
+        let mut tile = Tile::new();
+        tile.set_pixel(0, 0, 3);
+        tile.set_pixel(1, 0, 3);
+        let tiles = vec![Tile::new(), tile];
+
+        let mut tilemap = Tilemap::new(2, 2);
+        tilemap.set_entry(0, 0, TilemapEntry::new(1, 2, false, false, false));
+        tilemap.set_entry(1, 0, TilemapEntry::new(1, 2, true, false, false));
+        tilemap.set_entry(0, 1, TilemapEntry::new(500, 4, false, false, false)); // Missing tile
+
+        let counts = tilemap.color_frequency(&tiles);
+
+        // Two entries of tile 1 on sub-palette 2: 2 pixels of color 3 each
+        assert_eq!(counts[2][3], 4);
+        assert_eq!(counts[2][0], 2 * 62);
+        // Remaining entry uses tile 0 on sub-palette 0
+        assert_eq!(counts[0][0], 64);
+        // Missing tile contributes nothing
+        assert_eq!(counts[4].iter().sum::<u32>(), 0);
+    }
+
+    #[test]
+    fn test_chunked_tilemap_global_addressing() {
+        let mut map = ChunkedTilemap::new(600, 300);
+        assert_eq!(map.chunks_x(), 3);
+        assert_eq!(map.chunks_y(), 2);
+        assert_eq!(map.chunk(2, 1).unwrap().width(), 600 - 512);
+        assert_eq!(map.chunk(2, 1).unwrap().height(), 300 - 256);
+
+        let entry = TilemapEntry::new(77, 2, true, false, false);
+        map.set_entry(300, 10, entry);
+
+        assert_eq!(map.get_entry(300, 10), Some(entry));
+        assert_eq!(map.chunk(1, 0).unwrap().get_entry(44, 10), Some(entry));
+        assert_eq!(
+            map.chunk(0, 0).unwrap().get_entry(44, 10),
+            Some(TilemapEntry::default())
+        );
+
+        // Out of bounds
+        map.set_entry(600, 0, entry);
+        assert_eq!(map.get_entry(600, 0), None);
+        assert!(map.export_chunk_binary(3, 0).is_none());
+
+        let binary = map.export_chunk_binary(1, 0).unwrap();
+        assert_eq!(binary.len(), 256 * 256 * 2);
+        let offset = (10 * 256 + 44) * 2;
+        assert_eq!(
+            u16::from_le_bytes([binary[offset], binary[offset + 1]]),
+            entry.to_u16()
+        );
+    }
+
+    #[test]
+    fn test_chunked_tilemap_chunk_mut_keeps_grid() {
+        let mut map = ChunkedTilemap::new(600, 300);
+        let entry = TilemapEntry::new(12, 1, false, true, false);
+
+        let mut chunk = map.chunk_mut(2, 1).unwrap();
+        assert_eq!((chunk.width(), chunk.height()), (88, 44));
+        chunk.set_entry(87, 43, entry);
+        chunk.set_entry(88, 0, entry); // Outside the chunk: ignored
+        assert_eq!(chunk.get_entry(87, 43), Some(entry));
+        assert!(map.chunk_mut(3, 0).is_none());
+
+        // The write is visible at the matching global position and the
+        // chunk still matches the grid
+        assert_eq!(map.get_entry(599, 299), Some(entry));
+        assert_eq!(map.chunk(2, 1).unwrap().width(), 600 - 512);
+        assert_eq!(map.chunk(2, 1).unwrap().height(), 300 - 256);
+
+        map.chunk_mut(2, 1).unwrap().fill(entry);
+        assert_eq!(map.get_entry(512, 256), Some(entry));
+        map.chunk_mut(2, 1).unwrap().clear();
+        assert_eq!(map.get_entry(599, 299), Some(TilemapEntry::default()));
+        assert_eq!(map.get_entry(511, 255), Some(TilemapEntry::default()));
+    }
+
+    #[test]
+    fn test_tilemap_suspicious_entries() {
+        let mut tilemap = Tilemap::new(4, 4);
+        tilemap.set_entry(1, 2, TilemapEntry::new(0, 0, false, false, true));
+        tilemap.set_entry(3, 3, TilemapEntry::new(0, 5, true, false, false));
+        // Non-blank tiles with attributes are fine
+        tilemap.set_entry(0, 0, TilemapEntry::new(9, 5, true, true, true));
+
+        let findings = tilemap.suspicious_entries();
+        assert_eq!(
+            findings,
+            vec![
+                (1, 2, SuspiciousReason::BlankTileWithPriority),
+                (3, 3, SuspiciousReason::BlankTileWithPalette),
+                (3, 3, SuspiciousReason::BlankTileWithFlip),
+            ]
+        );
+
+        assert!(Tilemap::new(8, 8).suspicious_entries().is_empty());
+    }
+
+    #[test]
+    fn test_tilemap_export_binary_with_base() {
+        let mut tilemap = Tilemap::new(2, 1);
+        tilemap.set_entry(0, 0, TilemapEntry::new(128, 1, false, false, false));
+        tilemap.set_entry(1, 0, TilemapEntry::new(200, 2, true, false, false));
+
+        let binary = tilemap.export_binary_with_base(128).unwrap();
+        let first = TilemapEntry::from_u16(u16::from_le_bytes([binary[0], binary[1]]));
+        let second = TilemapEntry::from_u16(u16::from_le_bytes([binary[2], binary[3]]));
+        assert_eq!(first, TilemapEntry::new(0, 1, false, false, false));
+        assert_eq!(second, TilemapEntry::new(72, 2, true, false, false));
+
+        // Base of 0 matches the plain export
+        assert_eq!(
+            tilemap.export_binary_with_base(0),
+            Ok(tilemap.export_binary())
+        );
+
+        // An index below the base is an error
+        tilemap.set_entry(0, 0, TilemapEntry::new(127, 0, false, false, false));
+        assert!(tilemap.export_binary_with_base(128).is_err());
+    }
+
+    #[test]
+    fn test_tilemap_replace_tile_toggles_flips() {
+        let mut tilemap = Tilemap::new(3, 1);
+        tilemap.set_entry(0, 0, TilemapEntry::new(5, 2, false, false, true));
+        tilemap.set_entry(1, 0, TilemapEntry::new(5, 3, true, true, false));
+        tilemap.set_entry(2, 0, TilemapEntry::new(6, 0, false, false, false));
+
+        tilemap.replace_tile(5, 9, true, false);
+
+        assert_eq!(
+            tilemap.get_entry(0, 0),
+            Some(TilemapEntry::new(9, 2, true, false, true))
+        );
+        assert_eq!(
+            tilemap.get_entry(1, 0),
+            Some(TilemapEntry::new(9, 3, false, true, false))
+        );
+        // Other tiles are untouched
+        assert_eq!(
+            tilemap.get_entry(2, 0),
+            Some(TilemapEntry::new(6, 0, false, false, false))
+        );
+    }
+
+    #[test]
+    fn test_tilemap_entry_eq_ignoring_priority() {
+        let a = TilemapEntry::new(10, 2, true, false, false);
+        let b = TilemapEntry::new(10, 2, true, false, true);
+        assert_ne!(a, b);
+        assert!(a.eq_ignoring_priority(&b));
+
+        // Any other difference still counts
+        let c = TilemapEntry::new(10, 2, false, false, true);
+        assert!(!a.eq_ignoring_priority(&c));
+        assert!(!a.eq_ignoring_priority(&TilemapEntry::new(11, 2, true, false, false)));
+    }
+
+    #[test]
+    fn test_tilemap_entry_eq_ignoring_flips() {
+        let a = TilemapEntry::new(10, 2, true, false, true);
+        let b = TilemapEntry::new(10, 2, false, true, true);
+        assert!(a.eq_ignoring_flips(&b));
+        assert!(!a.eq_ignoring_flips(&TilemapEntry::new(10, 2, true, false, false)));
+        assert!(!a.eq_ignoring_flips(&TilemapEntry::new(10, 3, true, false, true)));
+    }
+
+    #[test]
+    fn test_tilemap_flip_stats() {
+        let mut tilemap = Tilemap::new(4, 2);
+        tilemap.set_entry(0, 0, TilemapEntry::new(1, 0, true, false, false));
+        tilemap.set_entry(1, 0, TilemapEntry::new(2, 0, true, false, false));
+        tilemap.set_entry(2, 0, TilemapEntry::new(3, 0, false, true, false));
+        tilemap.set_entry(3, 0, TilemapEntry::new(4, 0, true, true, true));
+
+        assert_eq!(
+            tilemap.flip_stats(),
+            FlipStats {
+                none: 4,
+                h_only: 2,
+                v_only: 1,
+                both: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_tilemap_pseudo_random_is_deterministic() {
+        let a = Tilemap::pseudo_random(16, 12, 99);
+        let b = Tilemap::pseudo_random(16, 12, 99);
+        assert_eq!(a, b);
+        assert_eq!(a.width(), 16);
+        assert_eq!(a.height(), 12);
+        assert_ne!(a, Tilemap::pseudo_random(16, 12, 100));
+    }
+
+    #[test]
+    fn test_tilemap_entry_set_tile_index_checked() {
+        let mut entry = TilemapEntry::new(5, 0, false, false, false);
+
+        assert_eq!(entry.set_tile_index_checked(1023), Ok(()));
+        assert_eq!(entry.tile_index(), 1023);
+
+        assert_eq!(
+            entry.set_tile_index_checked(1024),
+            Err(SemitileError::OutOfBounds {
+                index: 1024,
+                min: 0,
+                max: 1023
+            })
+        );
+        assert_eq!(entry.tile_index(), 1023); // Unchanged on error
+
+        // The lenient setter still clamps
+        entry.set_tile_index(5000);
+        assert_eq!(entry.tile_index(), 1023);
+    }
+
+    #[test]
+    fn test_tilemap_map_entries() {
+        let mut tilemap = Tilemap::new(4, 3);
+        tilemap.fill(TilemapEntry::new(7, 1, false, false, false));
+
+        tilemap.map_entries(|_, y, mut entry| {
+            if y == 0 {
+                entry.set_priority(true);
+            }
+            entry
+        });
+
+        for y in 0..3 {
+            for x in 0..4 {
+                let entry = tilemap.get_entry(x, y).unwrap();
+                assert_eq!(entry.priority(), y == 0);
+                assert_eq!(entry.tile_index(), 7);
+            }
+        }
+
+        // Coordinates are passed in row-major order
+        let mut visited = Vec::new();
+        tilemap.map_entries(|x, y, entry| {
+            visited.push((x, y));
+            entry
+        });
+        assert_eq!(visited[..5], [(0, 0), (1, 0), (2, 0), (3, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_tilemap_on_change() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut tilemap = Tilemap::new(4, 4);
+        let sink = Arc::clone(&events);
+        tilemap.on_change(Box::new(move |kind| sink.lock().unwrap().push(kind)));
+
+        tilemap.set_entry(1, 2, TilemapEntry::new(3, 0, false, false, false));
+        tilemap.set_entry(10, 10, TilemapEntry::default()); // Out of bounds: no event
+        tilemap.resize(4, 4); // No size change: no event
+        tilemap.resize(6, 5);
+        tilemap.fill(TilemapEntry::new(1, 0, false, false, false));
+        tilemap.clear();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ChangeKind::EntrySet { x: 1, y: 2 },
+                ChangeKind::Resized {
+                    width: 6,
+                    height: 5
+                },
+                ChangeKind::Filled,
+                ChangeKind::Cleared,
+            ]
+        );
+
+        // Clones don't inherit the callback and equality ignores it
+        let mut copy = tilemap.clone();
+        assert_eq!(copy, tilemap);
+        copy.clear();
+        assert_eq!(events.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_tilemap_walkability_grid() {
+        let mut tilemap = Tilemap::new(3, 2);
+        tilemap.set_entry(1, 0, TilemapEntry::new(8, 0, false, false, false));
+        tilemap.set_entry(2, 1, TilemapEntry::new(9, 3, true, false, false));
+        tilemap.set_entry(0, 1, TilemapEntry::new(4, 0, false, false, false));
+
+        let solid: HashSet<u16> = [8, 9].into_iter().collect();
+        let grid = tilemap.walkability_grid(&solid);
+
+        assert_eq!(grid, vec![true, false, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_tilemap_non_default_count() {
+        let mut tilemap = Tilemap::new(8, 8);
+        assert_eq!(tilemap.non_default_count(), 0);
+
+        tilemap.set_entry(0, 0, TilemapEntry::new(1, 0, false, false, false));
+        tilemap.set_entry(3, 4, TilemapEntry::new(0, 2, false, false, false));
+        tilemap.set_entry(7, 7, TilemapEntry::new(0, 0, false, false, true));
+        assert_eq!(tilemap.non_default_count(), 3);
+
+        tilemap.set_entry(3, 4, TilemapEntry::default());
+        assert_eq!(tilemap.non_default_count(), 2);
+    }
+
+    #[test]
+    fn test_tilemap_entry_byte_order() {
+        let entry = TilemapEntry::new(0x2A5, 5, true, false, true);
+        let mut tilemap = Tilemap::new(1, 1);
+        tilemap.set_entry(0, 0, entry);
+        let exported = tilemap.export_binary();
+
+        assert_eq!(entry.to_le_bytes(), [exported[0], exported[1]]);
+        assert_eq!(entry.to_be_bytes(), [exported[1], exported[0]]);
+        assert_eq!(TilemapEntry::from_le_bytes(entry.to_le_bytes()), entry);
+        assert_eq!(TilemapEntry::from_be_bytes(entry.to_be_bytes()), entry);
+    }
+
+    #[test]
+    fn test_tilemap_fill_with_flips() {
+        let mut expected = Tilemap::new(4, 3);
+        expected.fill(TilemapEntry::new(300, 6, true, false, true));
+
+        let mut tilemap = Tilemap::new(4, 3);
+        tilemap.fill_with_flips(300, 6, true, false, true);
+        assert_eq!(tilemap, expected);
+    }
+
+    #[test]
+    fn test_tilemap_same_layout() {
+        let mut a = Tilemap::new(3, 2);
+        let mut b = Tilemap::new(3, 2);
+        a.set_entry(0, 0, TilemapEntry::new(5, 1, true, false, false));
+        b.set_entry(0, 0, TilemapEntry::new(5, 4, true, false, true));
+        a.set_entry(2, 1, TilemapEntry::new(9, 0, false, true, false));
+        b.set_entry(2, 1, TilemapEntry::new(9, 7, false, true, false));
+        assert!(a.same_layout(&b));
+
+        b.set_entry(2, 1, TilemapEntry::new(9, 7, true, true, false));
+        assert!(!a.same_layout(&b));
+        b.set_entry(2, 1, TilemapEntry::new(10, 0, false, true, false));
+        assert!(!a.same_layout(&b));
+        assert!(!a.same_layout(&Tilemap::new(2, 3)));
+    }
+
+    #[test]
+    fn test_tilemap_edit_log_replay_reproduces_map() {
+        let mut target = Tilemap::new(4, 4);
+        let mut log = TilemapEditLog::new();
+        let edits = [
+            (0, 1, 1, TilemapEntry::new(3, 1, false, false, false)),
+            (1, 2, 3, TilemapEntry::new(7, 2, true, false, true)),
+            (2, 1, 1, TilemapEntry::new(4, 0, false, true, false)), // Overwrites the first edit
+            (3, 0, 0, TilemapEntry::new(1023, 7, true, true, true)),
+        ];
+        for (timestamp_index, x, y, entry) in edits {
+            target.set_entry(x, y, entry);
+            log.record(timestamp_index, x, y, entry);
+        }
+
+        let restored = TilemapEditLog::deserialize(&log.serialize()).unwrap();
+        assert_eq!(restored, log);
+
+        let mut map = Tilemap::new(4, 4);
+        restored.replay_onto(&mut map);
+        assert_eq!(map, target);
+    }
+
+    #[test]
+    fn test_tilemap_edit_log_orders_by_timestamp() {
+        let mut log = TilemapEditLog::new();
+        log.record(5, 0, 0, TilemapEntry::new(2, 0, false, false, false));
+        log.record(1, 0, 0, TilemapEntry::new(1, 0, false, false, false));
+
+        let mut map = Tilemap::new(1, 1);
+        log.replay_onto(&mut map);
+        assert_eq!(map.get_entry(0, 0).unwrap().tile_index(), 2);
+
+        assert_eq!(
+            TilemapEditLog::deserialize(&[0; 15]),
+            Err(SemitileError::InvalidLength {
+                expected: 20,
+                actual: 15
+            })
+        );
+    }
+
+    #[test]
+    fn test_tilemap_into_iterator() {
+        let mut tilemap = Tilemap::new(3, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                tilemap.set_entry(
+                    x,
+                    y,
+                    TilemapEntry::new((y * 3 + x) as u16, 0, false, false, false),
+                );
+            }
+        }
+
+        let mut count = 0;
+        for entry in &tilemap {
+            assert_eq!(entry.tile_index(), count);
+            count += 1;
+        }
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn test_tilemap_contains_tile() {
+        let mut tilemap = Tilemap::new(16, 16);
+        assert!(tilemap.contains_tile(0));
+        assert!(!tilemap.contains_tile(42));
+
+        tilemap.set_entry(15, 15, TilemapEntry::new(42, 3, true, false, false));
+        assert!(tilemap.contains_tile(42));
+
+        tilemap.fill(TilemapEntry::new(7, 0, false, false, false));
+        assert!(tilemap.contains_tile(7));
+        assert!(!tilemap.contains_tile(0));
+        assert!(!tilemap.contains_tile(42));
+
+        // The scan stops at the first match and only reads everything on a miss
+        tilemap.set_entry(3, 0, TilemapEntry::new(9, 0, false, false, false));
+        let mut visited = 0;
+        let entries = tilemap.into_iter().inspect(|_| visited += 1);
+        assert!(Tilemap::scan_for_tile(entries, 9));
+        assert_eq!(visited, 4);
+
+        visited = 0;
+        let entries = tilemap.into_iter().inspect(|_| visited += 1);
+        assert!(!Tilemap::scan_for_tile(entries, 42));
+        assert_eq!(visited, 16 * 16);
+    }
+
+    #[test]
+    fn test_tilemap_save_round_trips() {
+        let mut tilemap = Tilemap::new(20, 15);
+        tilemap.fill(TilemapEntry::new(12, 1, false, false, false));
+        for x in 0..20 {
+            tilemap.set_entry(
+                x,
+                7,
+                TilemapEntry::new(x as u16, 2, x % 2 == 0, false, true),
+            );
+        }
+
+        let raw = tilemap.save(SaveFormat::Raw);
+        assert_eq!(raw, tilemap.export_binary());
+        assert_eq!(Tilemap::import_binary(&raw, 20, 15), Some(tilemap.clone()));
+
+        let versioned = tilemap.save(SaveFormat::RawVersioned);
+        assert_eq!(&versioned[0..6], b"STMP\x01\x00");
+        assert_eq!(Tilemap::load(&versioned), Ok(tilemap.clone()));
+
+        let rle = tilemap.save(SaveFormat::Rle);
+        assert_eq!(&rle[0..6], b"STMP\x01\x01");
+        assert!(rle.len() < versioned.len());
+        assert_eq!(Tilemap::load(&rle), Ok(tilemap));
+    }
+
+    #[test]
+    fn test_tilemap_save_rle_long_runs() {
+        // 65536 identical entries need runs split at 255
+        let tilemap = Tilemap::new(256, 256);
+        let rle = tilemap.save(SaveFormat::Rle);
+        assert_eq!(rle.len(), 10 + 65536usize.div_ceil(255) * 3);
+        assert_eq!(Tilemap::load(&rle), Ok(tilemap));
+    }
+
+    #[test]
+    fn test_tilemap_load_errors() {
+        let tilemap = Tilemap::new(4, 4);
+        assert!(matches!(
+            Tilemap::load(&tilemap.save(SaveFormat::Raw)),
+            Err(SemitileError::Parse(_))
+        ));
+        assert_eq!(
+            Tilemap::load(b"STMP"),
+            Err(SemitileError::InvalidLength {
+                expected: 10,
+                actual: 4
+            })
+        );
+
+        let mut rle = tilemap.save(SaveFormat::Rle);
+        rle[10] -= 1; // Runs now cover 15 of 16 entries
+        assert!(matches!(Tilemap::load(&rle), Err(SemitileError::Parse(_))));
+
+        let mut versioned = tilemap.save(SaveFormat::RawVersioned);
+        versioned.pop();
+        assert_eq!(
+            Tilemap::load(&versioned),
+            Err(SemitileError::InvalidLength {
+                expected: 42,
+                actual: 41
+            })
+        );
+        versioned[4] = 2;
+        assert!(matches!(
+            Tilemap::load(&versioned),
+            Err(SemitileError::Parse(_))
+        ));
+    }
+
+    fn scanline_test_scene() -> (Tilemap, Vec<Tile>, Palette) {
+        let tiles: Vec<Tile> = (0..4).map(Tile::pseudo_random).collect();
+        let mut palette = Palette::new();
+        for sub in 0..8 {
+            for color in 0..16 {
+                palette.set_color(sub, color, Color::new(sub * 4, color * 2, 31 - color));
+            }
+        }
+        let mut tilemap = Tilemap::new(3, 2);
+        tilemap.set_entry(0, 0, TilemapEntry::new(1, 2, true, false, false));
+        tilemap.set_entry(1, 0, TilemapEntry::new(2, 5, false, true, false));
+        tilemap.set_entry(2, 0, TilemapEntry::new(3, 0, true, true, true));
+        tilemap.set_entry(0, 1, TilemapEntry::new(9, 1, false, false, false)); // Missing tile
+        tilemap.set_entry(2, 1, TilemapEntry::new(2, 7, false, false, false));
+        (tilemap, tiles, palette)
+    }
+
+    #[test]
+    fn test_tilemap_render_rgba_places_flipped_tiles() {
+        let (tilemap, tiles, palette) = scanline_test_scene();
+        let frame = tilemap.render_rgba(&tiles, &palette);
+        assert_eq!(frame.len(), 24 * 16 * 4);
+
+        // Pixel (8, 0) is the top-left of entry (1, 0), which is V-flipped
+        let color = palette.get_color(5, tiles[2].get_pixel(0, 7));
+        let (r, g, b) = color.to_rgb888();
+        assert_eq!(&frame[8 * 4..8 * 4 + 4], &[r, g, b, 255]);
+    }
+
+    #[test]
+    fn test_tilemap_scanlines_match_full_frame() {
+        let (tilemap, tiles, palette) = scanline_test_scene();
+        let frame = tilemap.render_rgba(&tiles, &palette);
+
+        let mut scanlines = Vec::new();
+        for line in 0..16 {
+            scanlines.extend(tilemap.render_scanline_rgba(&tiles, &palette, line, 0));
+        }
+        assert_eq!(scanlines, frame);
+    }
+
+    #[test]
+    fn test_tilemap_scanline_scroll_wraps() {
+        let (tilemap, tiles, palette) = scanline_test_scene();
+        let row = tilemap.render_scanline_rgba(&tiles, &palette, 3, 0);
+        let scrolled = tilemap.render_scanline_rgba(&tiles, &palette, 3, 10);
+        let len = row.len();
+        assert_eq!(&scrolled[..len - 40], &row[40..]);
+        assert_eq!(&scrolled[len - 40..], &row[..40]);
+        assert_eq!(
+            tilemap.render_scanline_rgba(&tiles, &palette, 3 + 16, 10 + 24),
+            scrolled
+        );
+    }
+
+    #[test]
+    fn test_tilemap_assign_palettes_by_coverage() {
+        let mut palette = Palette::new();
+        for color in 1..6 {
+            palette.set_color(3, color, Color::new(color * 5, 10, 20));
+        }
+        palette.set_color(1, 1, Color::new(31, 31, 31));
+
+        let mut uses_1_to_5 = Tile::new();
+        for x in 0..5 {
+            uses_1_to_5.set_pixel(x, 0, x as u8 + 1);
+        }
+        let mut uses_1 = Tile::new();
+        uses_1.set_pixel(0, 0, 1);
+        let tiles = [Tile::new(), uses_1_to_5, uses_1];
+
+        let mut tilemap = Tilemap::new(4, 1);
+        tilemap.set_entry(0, 0, TilemapEntry::new(1, 0, false, false, false));
+        tilemap.set_entry(1, 0, TilemapEntry::new(1, 6, true, false, false));
+        tilemap.set_entry(2, 0, TilemapEntry::new(2, 1, false, false, false));
+        tilemap.set_entry(3, 0, TilemapEntry::new(500, 5, false, false, false));
+        tilemap.assign_palettes_by_coverage(&tiles, &palette);
+
+        assert_eq!(tilemap.get_entry(0, 0).unwrap().palette_idx(), 3);
+        assert_eq!(tilemap.get_entry(1, 0).unwrap().palette_idx(), 3);
+        assert!(tilemap.get_entry(1, 0).unwrap().h_flip());
+        // Sub-palettes 1 and 3 both cover index 1; the current choice is kept
+        assert_eq!(tilemap.get_entry(2, 0).unwrap().palette_idx(), 1);
+        // Missing tile is left alone
+        assert_eq!(tilemap.get_entry(3, 0).unwrap().palette_idx(), 5);
+    }
+
+    #[test]
+    fn test_tilemap_assign_palettes_by_coverage_keeps_real_black() {
+        // Sub-palette 4 uses black as a real color in slot 1; sub-palette 1
+        // only defines slot 1
+        let mut palette = Palette::new();
+        palette.set_color(4, 1, Color::new(0, 0, 0));
+        palette.set_color(4, 2, Color::new(31, 0, 0));
+        palette.set_color(1, 1, Color::new(31, 31, 31));
+
+        let mut outlined = Tile::new();
+        for x in 0..8 {
+            outlined.set_pixel(x, 0, 1);
+        }
+        outlined.set_pixel(0, 1, 2);
+
+        let mut tilemap = Tilemap::new(1, 1);
+        tilemap.set_entry(0, 0, TilemapEntry::new(0, 0, false, false, false));
+        tilemap.assign_palettes_by_coverage(&[outlined], &palette);
+        assert_eq!(tilemap.get_entry(0, 0).unwrap().palette_idx(), 4);
+    }
+
+    #[test]
+    fn test_tilemap_sparse_roundtrip_is_smaller() {
+        let mut tilemap = Tilemap::new(32, 32);
+        tilemap.set_entry(0, 0, TilemapEntry::new(5, 1, false, false, false));
+        tilemap.set_entry(31, 0, TilemapEntry::new(1023, 7, true, false, true));
+        tilemap.set_entry(7, 19, TilemapEntry::new(0, 0, false, true, false));
+        tilemap.set_entry(31, 31, TilemapEntry::new(42, 3, true, true, false));
+
+        let sparse = tilemap.export_sparse();
+        assert_eq!(sparse.len(), 4 * 4);
+        assert!(sparse.len() < tilemap.export_binary().len());
+        assert_eq!(Tilemap::import_sparse(&sparse, 32, 32).unwrap(), tilemap);
+    }
+
+    #[test]
+    fn test_tilemap_sparse_import_rejects_bad_data() {
+        assert_eq!(
+            Tilemap::import_sparse(&[0, 0, 1], 4, 4),
+            Err(SemitileError::InvalidLength {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            Tilemap::import_sparse(&[0, 4, 1, 0], 4, 4),
+            Err(SemitileError::OutOfBounds {
+                index: 4,
+                min: 0,
+                max: 3
+            })
+        );
+        assert_eq!(
+            Tilemap::import_sparse(&[], 4, 4).unwrap(),
+            Tilemap::new(4, 4)
+        );
+    }
+
+    #[test]
+    fn test_tilemap_flood_fill_where_matches_tile_index() {
+        // 5x3 map of grass (tile 1, mixed flips) split by a wall column of tile 2
+        let mut tilemap = Tilemap::new(5, 3);
+        for y in 0..3 {
+            for x in 0..5 {
+                let entry = if x == 2 {
+                    TilemapEntry::new(2, 0, false, false, false)
+                } else {
+                    TilemapEntry::new(1, 0, x % 2 == 0, y % 2 == 0, false)
+                };
+                tilemap.set_entry(x, y, entry);
+            }
+        }
+
+        let water = TilemapEntry::new(1, 4, false, false, false);
+        tilemap.flood_fill_where(0, 1, water, |entry| entry.tile_index() == 1);
+
+        for y in 0..3 {
+            for x in 0..5 {
+                let entry = tilemap.get_entry(x, y).unwrap();
+                match x {
+                    0 | 1 => assert_eq!(entry, water),
+                    2 => assert_eq!(entry.tile_index(), 2),
+                    _ => assert_eq!(entry.palette_idx(), 0),
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json_round_trip() {
+        use crate::{Color, Palette, Tile};
+
+        let tile = Tile::pseudo_random(9);
+        let json = serde_json::to_string(&tile).unwrap();
+        assert_eq!(serde_json::from_str::<Tile>(&json).unwrap(), tile);
+
+        let color = Color::new(31, 4, 17);
+        assert_eq!(serde_json::to_string(&color).unwrap(), "[31,4,17]");
+        assert_eq!(serde_json::from_str::<Color>("[31,4,17]").unwrap(), color);
+
+        let mut palette = Palette::new();
+        palette.set_color(3, 7, color);
+        let json = serde_json::to_string(&palette).unwrap();
+        assert_eq!(serde_json::from_str::<Palette>(&json).unwrap(), palette);
+
+        let entry = TilemapEntry::new(1000, 5, true, false, true);
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(json, entry.to_u16().to_string());
+        assert_eq!(serde_json::from_str::<TilemapEntry>(&json).unwrap(), entry);
+
+        let tilemap = Tilemap::pseudo_random(5, 3, 77);
+        let json = serde_json::to_string(&tilemap).unwrap();
+        assert!(json.starts_with(r#"{"width":5,"height":3,"entries":["#));
+        assert_eq!(serde_json::from_str::<Tilemap>(&json).unwrap(), tilemap);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_inconsistent_tilemap() {
+        let json = r#"{"width":2,"height":2,"entries":[0,1,2]}"#;
+        assert!(serde_json::from_str::<Tilemap>(json).is_err());
+        let json = r#"{"width":0,"height":1,"entries":[]}"#;
+        assert!(serde_json::from_str::<Tilemap>(json).is_err());
+    }
+
+    #[test]
+    fn test_tilemap_on_change_covers_every_mutator() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut tilemap = Tilemap::new(2, 2);
+        let sink = Arc::clone(&events);
+        tilemap.on_change(Box::new(move |kind| sink.lock().unwrap().push(kind)));
+        let take = || std::mem::take(&mut *events.lock().unwrap());
+
+        tilemap.set_entry(1, 0, TilemapEntry::new(1, 0, false, false, false));
+        assert_eq!(take(), vec![ChangeKind::EntrySet { x: 1, y: 0 }]);
+
+        tilemap.resize(2, 3);
+        assert_eq!(
+            take(),
+            vec![ChangeKind::Resized {
+                width: 2,
+                height: 3
+            }]
+        );
+
+        tilemap.resize(2, 2);
+        take();
+        tilemap.fill(TilemapEntry::default());
+        assert_eq!(take(), vec![ChangeKind::Filled]);
+
+        tilemap.fill_with_flips(2, 0, true, false, false);
+        assert_eq!(take(), vec![ChangeKind::Filled]);
+
+        tilemap.clear();
+        assert_eq!(take(), vec![ChangeKind::Cleared]);
+
+        tilemap.flood_fill_where(0, 0, TilemapEntry::new(3, 0, false, false, false), |e| {
+            e.tile_index() == 0
+        });
+        assert_eq!(take().len(), 4);
+
+        // Only cells whose entry actually changes are reported
+        tilemap.map_entries(|x, y, entry| {
+            if (x, y) == (0, 1) {
+                TilemapEntry::new(5, 0, false, false, false)
+            } else {
+                entry
+            }
+        });
+        assert_eq!(take(), vec![ChangeKind::EntrySet { x: 0, y: 1 }]);
+
+        tilemap.replace_tile(5, 6, false, false);
+        assert_eq!(take(), vec![ChangeKind::EntrySet { x: 0, y: 1 }]);
+
+        // Tile 0 uses color 1, which only sub-palette 2 defines
+        let mut tile = Tile::new();
+        tile.set_pixel(0, 0, 1);
+        let mut palette = Palette::new();
+        palette.set_color(2, 1, Color::from_rgb888(255, 0, 0));
+        tilemap.set_entry(1, 1, TilemapEntry::new(0, 0, false, false, false));
+        take();
+        tilemap.assign_palettes_by_coverage(&[tile], &palette);
+        assert_eq!(take(), vec![ChangeKind::EntrySet { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn test_chunked_tilemap_on_change() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut chunked = ChunkedTilemap::new(300, 300);
+        let sink = Arc::clone(&events);
+        chunked.on_change(Box::new(move |cx, cy, kind| {
+            sink.lock().unwrap().push((cx, cy, kind))
+        }));
+
+        chunked.set_entry(260, 2, TilemapEntry::new(1, 0, false, false, false));
+        chunked.chunk_mut(0, 1).unwrap().set_entry(
+            3,
+            4,
+            TilemapEntry::new(2, 0, false, false, false),
+        );
+        chunked.chunk_mut(1, 1).unwrap().clear();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                (1, 0, ChangeKind::EntrySet { x: 4, y: 2 }),
+                (0, 1, ChangeKind::EntrySet { x: 3, y: 4 }),
+                (1, 1, ChangeKind::Cleared),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tilemaps_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Tilemap>();
+        assert_send_sync::<ChunkedTilemap>();
+    }
+
+    #[test]
+    fn test_tilemap_edit_log_keeps_out_of_range_coordinates() {
+        let mut log = TilemapEditLog::new();
+        log.record(0, 300, 255, TilemapEntry::new(1, 0, false, false, false));
+        log.record(1, 255, 1000, TilemapEntry::new(2, 0, false, false, false));
+        assert_eq!(log.edits()[0].1, 300);
+        assert_eq!(log.edits()[1].2, 1000);
+
+        let restored = TilemapEditLog::deserialize(&log.serialize()).unwrap();
+        assert_eq!(restored, log);
+
+        // Neither edit may land on the last row or column of a full-size map
+        let mut map = Tilemap::new(256, 256);
+        restored.replay_onto(&mut map);
+        assert_eq!(map, Tilemap::new(256, 256));
+    }
 }
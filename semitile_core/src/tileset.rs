@@ -0,0 +1,498 @@
+// Copyright (C) 2025 Connor Nolan connor@cnolandev.com
+//
+// This file is part of the Semikit project.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use crate::error::SemitileError;
+use crate::tile::Tile;
+
+/// Maximum number of tiles addressable by a tilemap entry (10-bit tile index)
+pub const MAX_TILES: usize = 1024;
+
+/// Represents an ordered collection of tiles addressed by tile index
+///
+/// Holds at most `MAX_TILES` tiles, matching the 10-bit tile index of
+/// `TilemapEntry`. A hash index of the tiles keeps lookups such as `find()`
+/// and `add_unique()` O(1) on average.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tileset {
+    tiles: Vec<Tile>,
+    /// Lowest index of each distinct tile
+    lookup: HashMap<Tile, u16>,
+}
+
+impl Tileset {
+    /// Creates a new empty tileset
+    pub fn new() -> Self {
+        Self {
+            tiles: Vec::new(),
+            lookup: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of tiles in the tileset
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns true if the tileset contains no tiles
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns the maximum number of tiles the tileset can hold (1024)
+    pub fn capacity(&self) -> usize {
+        MAX_TILES
+    }
+
+    /// Returns how many more tiles can be added before the tileset is full
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /// Returns true if no more tiles can be added
+    pub fn is_full(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Gets the tile at the given index
+    ///
+    /// Returns None if the index is out of bounds
+    pub fn get(&self, index: u16) -> Option<&Tile> {
+        self.tiles.get(index as usize)
+    }
+
+    /// Returns all tiles as a slice, ordered by tile index
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Appends a tile to the end of the tileset
+    ///
+    /// Returns the new tile's index, or `SemitileError::CapacityExceeded` if the
+    /// tileset is full
+    pub fn add(&mut self, tile: Tile) -> Result<u16, SemitileError> {
+        if self.is_full() {
+            return Err(SemitileError::CapacityExceeded {
+                capacity: self.capacity(),
+            });
+        }
+        let index = self.tiles.len() as u16;
+        self.lookup.entry(tile.clone()).or_insert(index);
+        self.tiles.push(tile);
+        Ok(index)
+    }
+
+    /// Adds a tile unless an identical one is already present
+    ///
+    /// Returns the index of the existing tile, or of the newly appended one.
+    /// Returns `SemitileError::CapacityExceeded` only if the tile is new and
+    /// the tileset is full.
+    pub fn add_unique(&mut self, tile: Tile) -> Result<u16, SemitileError> {
+        match self.find(&tile) {
+            Some(index) => Ok(index),
+            None => self.add(tile),
+        }
+    }
+
+    /// Finds the first tile with exactly the same pixels as `tile`
+    ///
+    /// Returns the tile's index, or None if no tile matches
+    pub fn find(&self, tile: &Tile) -> Option<u16> {
+        self.lookup.get(tile).copied()
+    }
+
+    /// Finds the first tile that matches `tile` under any flip combination
+    ///
+    /// Returns `(index, h_flip, v_flip)`, where drawing the stored tile with
+    /// those flip flags reproduces `tile`. Tiles are checked in index order and
+    /// an unflipped match is preferred for each tile. Returns None if no tile
+    /// matches.
+    pub fn find_flipped(&self, tile: &Tile) -> Option<(u16, bool, bool)> {
+        tile.flip_variants()
+            .iter()
+            .filter_map(|(variant, h_flip, v_flip)| {
+                self.find(variant).map(|index| (index, *h_flip, *v_flip))
+            })
+            .min_by_key(|&(index, _, _)| index)
+    }
+
+    /// Overwrites tiles from 4bpp planar data starting at `start_index`
+    ///
+    /// `data` holds 32 bytes per tile (see `Tile::from_planar()`). Tiles past
+    /// the end of the tileset are appended, and any gap between the current
+    /// end and `start_index` is filled with blank tiles. Tiles outside the
+    /// written range are left untouched, so empty `data` changes nothing.
+    ///
+    /// Returns `SemitileError::InvalidLength` if `data` is not a multiple of 32
+    /// bytes, or `SemitileError::CapacityExceeded` if the written range would
+    /// extend past `MAX_TILES`. The tileset is unchanged on error.
+    pub fn import_planar_at(&mut self, data: &[u8], start_index: u16) -> Result<(), SemitileError> {
+        if !data.len().is_multiple_of(32) {
+            return Err(SemitileError::InvalidLength {
+                expected: data.len().next_multiple_of(32),
+                actual: data.len(),
+            });
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let start = start_index as usize;
+        let end = start + data.len() / 32;
+        if end > self.capacity() {
+            return Err(SemitileError::CapacityExceeded {
+                capacity: self.capacity(),
+            });
+        }
+
+        if self.tiles.len() < end {
+            self.tiles.resize(end, Tile::new());
+        }
+        for (slot, chunk) in self.tiles[start..end].iter_mut().zip(data.chunks_exact(32)) {
+            *slot = Tile::try_from_planar_slice(chunk)?;
+        }
+        self.rebuild_lookup();
+        Ok(())
+    }
+
+    /// Exports the tileset as Rust source for embedding in builds
+    ///
+    /// Emits `pub const <const_name>: [[u8; 32]; N] = [...];` with one line of
+    /// 4bpp planar data per tile (see `Tile::to_planar()`)
+    pub fn export_rust(&self, const_name: &str) -> String {
+        let mut out = format!(
+            "pub const {}: [[u8; 32]; {}] = [\n",
+            const_name,
+            self.tiles.len()
+        );
+        for tile in &self.tiles {
+            let bytes: Vec<String> = tile
+                .to_planar()
+                .iter()
+                .map(|b| format!("0x{:02X}", b))
+                .collect();
+            out.push_str(&format!("    [{}],\n", bytes.join(", ")));
+        }
+        out.push_str("];\n");
+        out
+    }
+
+    /// Reorders tiles so that similar tiles are adjacent
+    ///
+    /// Starting from tile 0, greedily appends the remaining tile with the fewest
+    /// differing pixels from the previously placed one (ties go to the lowest
+    /// original index). Grouping similar tiles tends to improve downstream
+    /// compression.
+    ///
+    /// Returns a map from each old tile index to its new index, for updating
+    /// tilemaps that reference this tileset.
+    pub fn sort_by_similarity(&mut self) -> HashMap<u16, u16> {
+        let mut order: Vec<usize> = Vec::with_capacity(self.tiles.len());
+        let mut placed = vec![false; self.tiles.len()];
+
+        if !self.tiles.is_empty() {
+            order.push(0);
+            placed[0] = true;
+        }
+        while order.len() < self.tiles.len() {
+            let prev = &self.tiles[order[order.len() - 1]];
+            let next = (0..self.tiles.len())
+                .filter(|&i| !placed[i])
+                .min_by_key(|&i| pixel_difference(prev, &self.tiles[i]))
+                .expect("unplaced tile remains");
+            order.push(next);
+            placed[next] = true;
+        }
+
+        let mut remap = HashMap::with_capacity(order.len());
+        for (new_index, &old_index) in order.iter().enumerate() {
+            remap.insert(old_index as u16, new_index as u16);
+        }
+        self.tiles = order.into_iter().map(|i| self.tiles[i].clone()).collect();
+        self.rebuild_lookup();
+        remap
+    }
+
+    /// Recomputes the hash index after tiles were rewritten or reordered
+    fn rebuild_lookup(&mut self) {
+        self.lookup.clear();
+        for (index, tile) in self.tiles.iter().enumerate() {
+            self.lookup.entry(tile.clone()).or_insert(index as u16);
+        }
+    }
+}
+
+/// Counts the pixels that differ between two tiles
+fn pixel_difference(a: &Tile, b: &Tile) -> usize {
+    a.to_flat()
+        .iter()
+        .zip(b.to_flat())
+        .filter(|(pa, pb)| **pa != *pb)
+        .count()
+}
+
+impl<'a> IntoIterator for &'a Tileset {
+    type Item = &'a Tile;
+    type IntoIter = std::slice::Iter<'a, Tile>;
+
+    /// Iterates over tiles in tile index order
+    fn into_iter(self) -> Self::IntoIter {
+        self.tiles.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tileset_new_is_empty() {
+        let tileset = Tileset::new();
+        assert!(tileset.is_empty());
+        assert_eq!(tileset.len(), 0);
+        assert!(tileset.get(0).is_none());
+    }
+
+    #[test]
+    fn test_tileset_add_and_get() {
+        let mut tileset = Tileset::new();
+        let mut tile = Tile::new();
+        tile.set_pixel(2, 3, 9);
+
+        assert_eq!(tileset.add(Tile::new()), Ok(0));
+        assert_eq!(tileset.add(tile.clone()), Ok(1));
+        assert_eq!(tileset.get(1), Some(&tile));
+        assert_eq!(tileset.tiles().len(), 2);
+    }
+
+    #[test]
+    fn test_tileset_capacity_and_remaining() {
+        let mut tileset = Tileset::new();
+        assert_eq!(tileset.capacity(), MAX_TILES);
+        assert_eq!(tileset.capacity(), 1024);
+        assert_eq!(tileset.remaining(), 1024);
+
+        tileset.add(Tile::new()).unwrap();
+        tileset.add(Tile::new()).unwrap();
+        assert_eq!(tileset.remaining(), 1022);
+        assert!(!tileset.is_full());
+
+        while tileset.add(Tile::new()).is_ok() {}
+        assert_eq!(tileset.len(), 1024);
+        assert_eq!(tileset.remaining(), 0);
+        assert!(tileset.is_full());
+        assert!(tileset.add(Tile::new()).is_err());
+    }
+
+    #[test]
+    fn test_tileset_sort_by_similarity() {
+        // Tiles with 0, 40, 1 and 41 pixels set: similar pairs are 0/2 and 1/3
+        let mut tiles = Vec::new();
+        for count in [0, 40, 1, 41] {
+            let mut tile = Tile::new();
+            for i in 0..count {
+                tile.set_pixel(i % 8, i / 8, 5);
+            }
+            tiles.push(tile);
+        }
+        let mut tileset = Tileset::new();
+        for tile in &tiles {
+            tileset.add(tile.clone()).unwrap();
+        }
+
+        let remap = tileset.sort_by_similarity();
+
+        // The remap is a permutation of 0..len
+        assert_eq!(remap.len(), 4);
+        let mut new_indices: Vec<u16> = remap.values().copied().collect();
+        new_indices.sort();
+        assert_eq!(new_indices, vec![0, 1, 2, 3]);
+
+        // Every tile moved to where the remap says
+        for (old, new) in &remap {
+            assert_eq!(tileset.get(*new), Some(&tiles[*old as usize]));
+        }
+
+        // Similar tiles ended up adjacent
+        assert_eq!(remap[&0], 0);
+        assert_eq!(remap[&2], 1);
+        assert_eq!(remap[&1], 2);
+        assert_eq!(remap[&3], 3);
+    }
+
+    #[test]
+    fn test_tileset_export_rust() {
+        let mut tile = Tile::new();
+        tile.set_pixel(0, 0, 15);
+        let mut tileset = Tileset::new();
+        tileset.add(Tile::new()).unwrap();
+        tileset.add(tile.clone()).unwrap();
+
+        let source = tileset.export_rust("TILES");
+        assert!(source.starts_with("pub const TILES: [[u8; 32]; 2] = [\n"));
+
+        let rows: Vec<Vec<u8>> = source
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix('[')?.strip_suffix("],"))
+            .map(|row| {
+                row.split(", ")
+                    .map(|b| u8::from_str_radix(b.trim_start_matches("0x"), 16).unwrap())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1], tile.to_planar().to_vec());
+    }
+
+    #[test]
+    fn test_tileset_import_planar_at() {
+        let mut tileset = Tileset::new();
+        for i in 0..16 {
+            let mut tile = Tile::new();
+            tile.set_pixel(0, 0, i as u8);
+            tileset.add(tile).unwrap();
+        }
+        let original = tileset.clone();
+
+        let mut data = Vec::new();
+        for color in [13, 14, 15] {
+            let mut tile = Tile::new();
+            tile.set_pixel(7, 7, color);
+            data.extend_from_slice(&tile.to_planar());
+        }
+        tileset.import_planar_at(&data, 10).unwrap();
+
+        assert_eq!(tileset.len(), 16);
+        for i in 0..16u16 {
+            let tile = tileset.get(i).unwrap();
+            if (10..=12).contains(&i) {
+                assert_eq!(tile.get_pixel(0, 0), 0);
+                assert_eq!(tile.get_pixel(7, 7), 13 + (i - 10) as u8);
+            } else {
+                assert_eq!(tile, original.get(i).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_tileset_import_planar_at_empty_data() {
+        let mut tileset = Tileset::new();
+        tileset.add(Tile::pseudo_random(1)).unwrap();
+        let original = tileset.clone();
+
+        // Nothing is written, so the tileset is neither padded nor checked
+        // against capacity
+        tileset.import_planar_at(&[], 10).unwrap();
+        tileset.import_planar_at(&[], u16::MAX).unwrap();
+        assert_eq!(tileset, original);
+        assert_eq!(tileset.len(), 1);
+    }
+
+    #[test]
+    fn test_tileset_import_planar_at_extends_and_errors() {
+        let mut tileset = Tileset::new();
+        tileset.import_planar_at(&[0xFF; 32], 2).unwrap();
+        assert_eq!(tileset.len(), 3);
+        assert_eq!(tileset.get(0), Some(&Tile::new()));
+        assert_eq!(tileset.get(2).unwrap().get_pixel(0, 0), 15);
+
+        assert_eq!(
+            tileset.import_planar_at(&[0; 33], 0),
+            Err(SemitileError::InvalidLength {
+                expected: 64,
+                actual: 33
+            })
+        );
+        assert_eq!(
+            tileset.import_planar_at(&[0; 64], 1023),
+            Err(SemitileError::CapacityExceeded { capacity: 1024 })
+        );
+        assert_eq!(tileset.len(), 3);
+    }
+
+    #[test]
+    fn test_tileset_find() {
+        let mut tileset = Tileset::new();
+        let mut a = Tile::new();
+        a.set_pixel(1, 2, 3);
+        let mut b = Tile::new();
+        b.set_pixel(0, 0, 7);
+        b.set_pixel(1, 0, 8);
+        tileset.add(a.clone()).unwrap();
+        tileset.add(b.clone()).unwrap();
+
+        assert_eq!(tileset.find(&b), Some(1));
+        assert_eq!(tileset.find(&a), Some(0));
+        assert_eq!(tileset.find(&b.flipped(true, false)), None);
+    }
+
+    #[test]
+    fn test_tileset_find_flipped() {
+        let mut tileset = Tileset::new();
+        tileset.add(Tile::new()).unwrap();
+        let mut tile = Tile::new();
+        tile.set_pixel(0, 0, 7);
+        tile.set_pixel(1, 0, 8);
+        tileset.add(tile.clone()).unwrap();
+
+        assert_eq!(tileset.find_flipped(&tile), Some((1, false, false)));
+        assert_eq!(
+            tileset.find_flipped(&tile.flipped(true, false)),
+            Some((1, true, false))
+        );
+        assert_eq!(
+            tileset.find_flipped(&tile.flipped(true, true)),
+            Some((1, true, true))
+        );
+
+        let mut other = Tile::new();
+        other.set_pixel(4, 4, 1);
+        assert_eq!(tileset.find_flipped(&other), None);
+    }
+
+    #[test]
+    fn test_tileset_into_iterator() {
+        let mut tileset = Tileset::new();
+        for seed in 0..3 {
+            tileset.add(Tile::pseudo_random(seed)).unwrap();
+        }
+
+        let mut count = 0;
+        for tile in &tileset {
+            assert_eq!(tile, &Tile::pseudo_random(count));
+            count += 1;
+        }
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_tileset_add_unique_dedups_many_tiles() {
+        let distinct: Vec<Tile> = (0..500).map(Tile::pseudo_random).collect();
+        let mut tileset = Tileset::new();
+        for i in 0..10_000 {
+            let tile = distinct[(i * 7) % distinct.len()].clone();
+            let index = tileset.add_unique(tile.clone()).unwrap();
+            assert_eq!(tileset.get(index), Some(&tile));
+        }
+        assert_eq!(tileset.len(), distinct.len());
+        for tile in &distinct {
+            let index = tileset.find(tile).unwrap();
+            assert_eq!(tileset.get(index), Some(tile));
+        }
+    }
+}
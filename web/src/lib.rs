@@ -59,6 +59,13 @@ impl WasmTile {
         self.inner.get_pixel(x, y)
     }
 
+    /// Paints a square brush of the given Chebyshev radius centered on (cx, cy)
+    ///
+    /// The brush is clipped to the tile bounds. If color > 15, the operation is ignored
+    pub fn paint(&mut self, cx: usize, cy: usize, radius: usize, color: u8) {
+        self.inner.paint(cx, cy, radius, color);
+    }
+
     /// Converts the tile to 4bpp planar format (32 bytes)
     ///
     /// Returns a Uint8Array that can be used in JavaScript
@@ -72,13 +79,15 @@ impl WasmTile {
     /// Returns null if data length is not exactly 32 bytes
     #[wasm_bindgen(js_name = fromPlanar)]
     pub fn from_planar(data: &[u8]) -> Option<WasmTile> {
-        if data.len() != 32 {
-            return None;
-        }
-        let arr: [u8; 32] = data.try_into().ok()?;
-        Some(Self {
-            inner: Tile::from_planar(&arr),
-        })
+        Tile::try_from_planar_slice(data)
+            .ok()
+            .map(|inner| Self { inner })
+    }
+}
+
+impl Default for WasmTile {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -136,6 +145,72 @@ impl WasmColor {
         self.inner.to_rgb555()
     }
 
+    /// Creates a color from RGB565 format (16-bit)
+    #[wasm_bindgen(js_name = fromRgb565)]
+    pub fn from_rgb565(value: u16) -> Self {
+        Self {
+            inner: Color::from_rgb565(value),
+        }
+    }
+
+    /// Converts the color to RGB565 format (16-bit)
+    #[wasm_bindgen(js_name = toRgb565)]
+    pub fn to_rgb565(&self) -> u16 {
+        self.inner.to_rgb565()
+    }
+
+    /// Parses a `#RRGGBB` or `#RGB` hex string (the `#` is optional)
+    ///
+    /// Returns null on malformed input
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(s: &str) -> Option<WasmColor> {
+        Color::from_hex(s).map(|inner| Self { inner })
+    }
+
+    /// Formats the color as a `#RRGGBB` hex string
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        self.inner.to_hex()
+    }
+
+    /// Creates a color from normalized float components (0.0-1.0)
+    ///
+    /// Values are clamped to 0.0-1.0 and scaled to the 5-bit range
+    #[wasm_bindgen(js_name = fromRgbF32)]
+    pub fn from_rgb_f32(r: f32, g: f32, b: f32) -> Self {
+        Self {
+            inner: Color::from_rgb_f32(r, g, b),
+        }
+    }
+
+    /// Converts the color to normalized float components
+    ///
+    /// Returns an array [r, g, b] with values in 0.0-1.0
+    #[wasm_bindgen(js_name = toRgbF32)]
+    pub fn to_rgb_f32(&self) -> Vec<f32> {
+        let (r, g, b) = self.inner.to_rgb_f32();
+        vec![r, g, b]
+    }
+
+    /// Creates a color from YUV (BT.601) components
+    ///
+    /// Resulting RGB values are clamped and quantized to 5 bits
+    #[wasm_bindgen(js_name = fromYuv)]
+    pub fn from_yuv(y: f32, u: f32, v: f32) -> Self {
+        Self {
+            inner: Color::from_yuv(y, u, v),
+        }
+    }
+
+    /// Converts the color to YUV (BT.601)
+    ///
+    /// Returns an array [y, u, v]
+    #[wasm_bindgen(js_name = toYuv)]
+    pub fn to_yuv(&self) -> Vec<f32> {
+        let (y, u, v) = self.inner.to_yuv();
+        vec![y, u, v]
+    }
+
     /// Returns the individual RGB components (0-31 range)
     ///
     /// Returns an array [r, g, b]
@@ -191,6 +266,13 @@ impl WasmPalette {
         self.inner.set_color(palette_idx, color_idx, color.inner);
     }
 
+    /// Reduces every color channel to `levels` evenly spaced steps across 0-31
+    ///
+    /// `levels` is clamped to 2-32; 2 collapses each channel to 0 or 31
+    pub fn posterize(&mut self, levels: u8) {
+        self.inner.posterize(levels);
+    }
+
     /// Exports the entire palette as binary data (512 bytes)
     ///
     /// Format: 256 colors × 2 bytes (RGB555, little-endian)
@@ -209,6 +291,12 @@ impl WasmPalette {
     }
 }
 
+impl Default for WasmPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 //=============================================================================
 // TilemapEntry WASM Bindings
 //=============================================================================
@@ -382,6 +470,22 @@ impl WasmTilemap {
         Tilemap::import_binary(data, width, height).map(|inner| Self { inner })
     }
 
+    /// Exports only non-default entries as 4-byte records (x, y, entry LE)
+    #[wasm_bindgen(js_name = exportSparse)]
+    pub fn export_sparse(&self) -> Vec<u8> {
+        self.inner.export_sparse()
+    }
+
+    /// Imports a tilemap from `exportSparse` data
+    ///
+    /// Returns null if the data is truncated or a record lies outside the map
+    #[wasm_bindgen(js_name = importSparse)]
+    pub fn import_sparse(data: &[u8], width: usize, height: usize) -> Option<WasmTilemap> {
+        Tilemap::import_sparse(data, width, height)
+            .ok()
+            .map(|inner| Self { inner })
+    }
+
     /// Resizes the tilemap to new dimensions
     ///
     /// # Arguments
@@ -402,4 +506,20 @@ impl WasmTilemap {
     pub fn fill(&mut self, entry: &WasmTilemapEntry) {
         self.inner.fill(entry.inner);
     }
+
+    /// Fills the entire tilemap with an entry built from its fields
+    ///
+    /// Equivalent to `fill(new WasmTilemapEntry(tile, palette, hFlip, vFlip, priority))`
+    #[wasm_bindgen(js_name = fillWithFlips)]
+    pub fn fill_with_flips(
+        &mut self,
+        tile: u16,
+        palette: u8,
+        h_flip: bool,
+        v_flip: bool,
+        priority: bool,
+    ) {
+        self.inner
+            .fill_with_flips(tile, palette, h_flip, v_flip, priority);
+    }
 }